@@ -0,0 +1,52 @@
+// Evaluation speed of the estimators: cargo bench --bench estimators
+use std::hint::black_box;
+use std::time::Instant;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use checkers::{analysis, Board, CheckersColor, CountEstimator, Estimator, GameState, GameStatus, NeuralEstimator};
+
+// Positions from random games, so that the benchmark sees openings, middle games and endings.
+fn positions(count: usize, rng: &mut StdRng) -> Vec<Board> {
+    let mut positions = Vec::with_capacity(count);
+    while positions.len() < count {
+        let mut state = GameState::new(3);
+        while state.status() == GameStatus::InProgress && positions.len() < count {
+            let moves = state.legal_moves();
+            state.apply(&moves[rng.gen_range(0..moves.len())]).unwrap();
+            positions.push(state.board());
+        }
+    }
+    positions
+}
+
+fn bench_estimate(name: &str, estimator: &dyn Estimator, positions: &[Board], rounds: usize) {
+    let start = Instant::now();
+    for _ in 0..rounds {
+        for &board in positions {
+            black_box(estimator.estimate(black_box(board), CheckersColor::White, false));
+        }
+    }
+    let elapsed = start.elapsed();
+    let per_call = elapsed.as_nanos() as f64 / (rounds * positions.len()) as f64;
+    println!("{:<24} {:>10.1} ns/estimate", name, per_call);
+}
+
+fn bench_search(name: &str, estimator: &dyn Estimator, depth: usize) {
+    let start = Instant::now();
+    let result = analysis::analyse(Board::new(3), CheckersColor::White, depth, estimator);
+    let elapsed = start.elapsed();
+    println!("{:<24} {:>10} nodes at depth {} in {:?}", name, result.nodes, depth, elapsed);
+}
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let positions = positions(10000, &mut rng);
+    let count = CountEstimator::new(1, 3);
+    let small = NeuralEstimator::new(&[32], &mut rng);
+    let large = NeuralEstimator::new(&[128, 32], &mut rng);
+    bench_estimate("count", &count, &positions, 100);
+    bench_estimate("neural 128-32-1", &small, &positions, 20);
+    bench_estimate("neural 128-128-32-1", &large, &positions, 5);
+    bench_search("search count", &count, 6);
+    bench_search("search neural 128-32-1", &small, 6);
+}
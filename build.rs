@@ -0,0 +1,19 @@
+fn main() {
+    #[cfg(feature = "ffi")]
+    generate_header();
+}
+
+// Generates checkers.h from src/ffi.rs into OUT_DIR, the ffi tests check that include/checkers.h matches it.
+#[cfg(feature = "ffi")]
+fn generate_header() {
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir)).unwrap();
+    cbindgen::Builder::new()
+        .with_src(format!("{}/src/ffi.rs", crate_dir))
+        .with_config(config)
+        .generate()
+        .expect("Unable to generate the C header")
+        .write_to_file(format!("{}/checkers.h", std::env::var("OUT_DIR").unwrap()));
+}
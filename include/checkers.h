@@ -0,0 +1,163 @@
+#ifndef CHECKERS_H
+#define CHECKERS_H
+
+/* Generated by cbindgen from src/ffi.rs, do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define CHECKERS_MAX_PATH 16
+
+typedef enum CheckersSide {
+  CHECKERS_SIDE_WHITE = 0,
+  CHECKERS_SIDE_BLACK,
+  CHECKERS_SIDE_INVALID,
+} CheckersSide;
+
+typedef enum CheckersStatus {
+  CHECKERS_STATUS_OK = 0,
+  CHECKERS_STATUS_NULL_POINTER,
+  CHECKERS_STATUS_INVALID_ARGUMENT,
+  CHECKERS_STATUS_ILLEGAL_MOVE,
+  CHECKERS_STATUS_GAME_OVER,
+  CHECKERS_STATUS_NO_MOVES,
+} CheckersStatus;
+
+typedef enum CheckersOutcome {
+  CHECKERS_OUTCOME_IN_PROGRESS = 0,
+  CHECKERS_OUTCOME_WHITE_WINS,
+  CHECKERS_OUTCOME_BLACK_WINS,
+  CHECKERS_OUTCOME_DRAW,
+  CHECKERS_OUTCOME_INVALID,
+} CheckersOutcome;
+
+typedef struct CheckersBoard CheckersBoard;
+
+typedef struct CheckersGame CheckersGame;
+
+typedef struct CheckersMove {
+  uint8_t squares[CHECKERS_MAX_PATH];
+  uint8_t length;
+  bool is_capture;
+} CheckersMove;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Initial position with 1-3 rows of pawns, NULL for other values. Free with checkers_board_free.
+struct CheckersBoard *checkers_board_new(uint32_t rows);
+
+// Position from a FEN string such as "W:W21,22:B1,2", NULL if it cannot be parsed.
+//
+// # Safety
+// `fen` must be NULL or a NUL-terminated string.
+struct CheckersBoard *checkers_board_from_fen(const char *fen);
+
+// # Safety
+// `board` must be NULL or a handle returned by this library that was not freed yet.
+void checkers_board_free(struct CheckersBoard *board);
+
+// Writes the FEN of the position as a NUL-terminated string into `buffer` when it fits
+// and returns its length without the terminator, so a call with a NULL buffer gives the size to allocate.
+//
+// # Safety
+// `board` must be a valid handle, `buffer` NULL or writable for `capacity` bytes.
+size_t checkers_board_fen(const struct CheckersBoard *board,
+                          char *buffer,
+                          size_t capacity);
+
+// Returns `CHECKERS_SIDE_INVALID` for a NULL `board`.
+//
+// # Safety
+// `board` must be NULL or a valid handle.
+enum CheckersSide checkers_board_side_to_move(const struct CheckersBoard *board);
+
+// Writes up to `capacity` legal moves to `out` and returns the number of legal moves.
+//
+// # Safety
+// `board` must be a valid handle, `out` NULL or writable for `capacity` moves.
+size_t checkers_board_legal_moves(const struct CheckersBoard *board,
+                                  struct CheckersMove *out,
+                                  size_t capacity);
+
+// Plays a legal move given with its full path, the side to move changes.
+//
+// # Safety
+// `board` must be a valid handle, `game_move` a valid pointer.
+enum CheckersStatus checkers_board_apply(struct CheckersBoard *board,
+                                         const struct CheckersMove *game_move);
+
+// A NULL `board` has no moves to play and counts as over.
+//
+// # Safety
+// `board` must be NULL or a valid handle.
+bool checkers_board_is_game_over(const struct CheckersBoard *board);
+
+// Searches `depth` plies deep; with a non-zero `time_limit_ms` the search deepens iteratively
+// up to `depth` and stops at the time limit. `score` may be NULL.
+//
+// # Safety
+// `board` must be a valid handle, `best` a writable pointer.
+enum CheckersStatus checkers_board_search(const struct CheckersBoard *board,
+                                          uint32_t depth,
+                                          uint32_t time_limit_ms,
+                                          struct CheckersMove *best,
+                                          int32_t *score);
+
+// New game from the initial position with 1-3 rows of pawns, NULL for other values. Free with checkers_game_free.
+struct CheckersGame *checkers_game_new(uint32_t rows);
+
+// # Safety
+// `fen` must be NULL or a NUL-terminated string.
+struct CheckersGame *checkers_game_from_fen(const char *fen);
+
+// # Safety
+// `game` must be NULL or a handle returned by this library that was not freed yet.
+void checkers_game_free(struct CheckersGame *game);
+
+// Current position of the game as a new board handle, free it with checkers_board_free.
+//
+// # Safety
+// `game` must be a valid handle.
+struct CheckersBoard *checkers_game_board(const struct CheckersGame *game);
+
+// # Safety
+// `game` must be a valid handle, `out` NULL or writable for `capacity` moves.
+size_t checkers_game_legal_moves(const struct CheckersGame *game,
+                                 struct CheckersMove *out,
+                                 size_t capacity);
+
+// # Safety
+// `game` must be a valid handle, `game_move` a valid pointer.
+enum CheckersStatus checkers_game_apply(struct CheckersGame *game,
+                                        const struct CheckersMove *game_move);
+
+// Takes back the last move, NoMoves when there is nothing to undo.
+//
+// # Safety
+// `game` must be a valid handle.
+enum CheckersStatus checkers_game_undo(struct CheckersGame *game);
+
+// Returns `CHECKERS_OUTCOME_INVALID` for a NULL `game`.
+//
+// # Safety
+// `game` must be NULL or a valid handle.
+enum CheckersOutcome checkers_game_outcome(const struct CheckersGame *game);
+
+// # Safety
+// `game` must be a valid handle, `best` a writable pointer.
+enum CheckersStatus checkers_game_search(const struct CheckersGame *game,
+                                         uint32_t depth,
+                                         uint32_t time_limit_ms,
+                                         struct CheckersMove *best,
+                                         int32_t *score);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* CHECKERS_H */
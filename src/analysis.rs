@@ -0,0 +1,185 @@
+use std::time::Duration;
+use crate::Instant;
+use crate::{Board, CheckersColor, MoveExecutor};
+use crate::board_estimators::Estimator;
+use crate::moves::GameMove;
+use crate::statistics::SearchStats;
+
+#[derive(Clone, Debug)]
+pub struct MoveScore {
+    pub game_move: GameMove,
+    pub score: i32,
+    pub line: Vec<GameMove>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Analysis {
+    pub color: CheckersColor,
+    pub depth: usize,
+    pub moves: Vec<MoveScore>,
+    pub principal_variation: Vec<GameMove>,
+    pub nodes: usize,
+    pub leaf_nodes: usize,
+    pub elapsed: Duration,
+    // Counters of the whole search, with analyse_timed those of every iteration.
+    pub stats: SearchStats,
+}
+
+impl Analysis {
+    pub fn best(&self) -> Option<&MoveScore> {
+        self.moves.first()
+    }
+
+    pub fn score(&self) -> Option<i32> {
+        self.best().map(|m| m.score)
+    }
+}
+
+pub fn format_score(score: i32) -> String {
+    if score > i32::MAX / 2 {
+        "win".to_string()
+    } else if score < i32::MIN / 2 {
+        "loss".to_string()
+    } else {
+        format!("{:+}", score)
+    }
+}
+
+struct Searcher<'a> {
+    estimator: &'a dyn Estimator,
+    color: CheckersColor,
+    depth: usize,
+    stats: SearchStats,
+    deadline: Option<Instant>,
+    aborted: bool,
+}
+
+// A depth of 0 is searched as 1, there is no analysis without the moves of the position.
+pub fn analyse(board: Board, color: CheckersColor, depth: usize, estimator: &dyn Estimator) -> Analysis {
+    analyse_moves(board, color, &MoveExecutor::get_legal_moves(&board, color), depth, estimator)
+}
+
+// Same as analyse with only the given moves of the position searched.
+pub fn analyse_moves(board: Board, color: CheckersColor, moves: &[GameMove], depth: usize, estimator: &dyn Estimator) -> Analysis {
+    let mut stats = SearchStats::new();
+    let mut analysis = search(board, color, moves, depth.max(1), estimator, None, &mut stats).unwrap();
+    stats.elapsed = analysis.elapsed;
+    analysis.stats = stats;
+    analysis
+}
+
+// Iterative deepening up to max_depth; a depth that does not finish before the time limit is thrown away.
+// Depth 1 always finishes, also when max_depth is 0, so there is a best move whenever the side to move has one.
+pub fn analyse_timed(board: Board, color: CheckersColor, max_depth: usize, time_limit: Duration, estimator: &dyn Estimator) -> Analysis {
+    let start = Instant::now();
+    let deadline = start + time_limit;
+    let legal_moves = MoveExecutor::get_legal_moves(&board, color);
+    let mut stats = SearchStats::new();
+    let mut analysis = search(board, color, &legal_moves, 1, estimator, None, &mut stats).unwrap();
+    for depth in 2..=max_depth {
+        match search(board, color, &legal_moves, depth, estimator, Some(deadline), &mut stats) {
+            Some(deeper) => analysis = deeper,
+            None => break,
+        }
+    }
+    analysis.elapsed = start.elapsed();
+    stats.elapsed = analysis.elapsed;
+    analysis.stats = stats;
+    analysis
+}
+
+// The counters of the search are added to stats, also when it is stopped by the deadline.
+fn search(board: Board, color: CheckersColor, root_moves: &[GameMove], depth: usize, estimator: &dyn Estimator,
+          deadline: Option<Instant>, stats: &mut SearchStats) -> Option<Analysis> {
+    let start = Instant::now();
+    let mut searcher = Searcher {
+        estimator,
+        color,
+        depth,
+        stats: SearchStats::new(),
+        deadline,
+        aborted: false,
+    };
+    searcher.stats.expanded_nodes += 1;
+    searcher.stats.children += root_moves.len();
+    let mut moves = Vec::new();
+    for game_move in root_moves.iter().cloned() {
+        let new_board = MoveExecutor::promote_to_queen(&MoveExecutor::execute(&board, &game_move));
+        let (score, line) = searcher.minmax(new_board, depth - 1, color.opposite_color(), false);
+        if searcher.aborted {
+            stats.add(&searcher.stats);
+            return None;
+        }
+        moves.push(MoveScore {
+            game_move,
+            score,
+            line,
+        });
+    }
+    moves.sort_by_key(|m| std::cmp::Reverse(m.score));
+    let principal_variation = match moves.first() {
+        Some(best) => {
+            let mut pv = vec![best.game_move.clone()];
+            pv.extend(best.line.iter().cloned());
+            pv
+        }
+        None => Vec::new(),
+    };
+    let elapsed = start.elapsed();
+    stats.add(&searcher.stats);
+    stats.complete_depth(depth, elapsed);
+    Some(Analysis {
+        color,
+        depth,
+        moves,
+        principal_variation,
+        nodes: searcher.stats.nodes,
+        leaf_nodes: searcher.stats.leaf_evaluations,
+        elapsed,
+        stats: searcher.stats,
+    })
+}
+
+impl Searcher<'_> {
+    fn minmax(&mut self, board: Board, depth: usize, current_color: CheckersColor, maximising: bool) -> (i32, Vec<GameMove>) {
+        self.stats.nodes += 1;
+        if self.aborted || self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            self.aborted = true;
+            return (0, Vec::new());
+        }
+        if depth == 0 {
+            self.stats.leaf_evaluations += 1;
+            return (self.estimator.estimate(board, self.color, false), Vec::new());
+        }
+        let legal_moves = MoveExecutor::get_legal_moves(&board, current_color);
+        if legal_moves.is_empty() {
+            self.stats.leaf_evaluations += 1;
+            let current_estimation = self.estimator.estimate(board, self.color, true);
+            let distance = (self.depth - depth) as i32;
+            let score = if maximising {
+                current_estimation.saturating_add(distance)
+            } else {
+                current_estimation.saturating_sub(distance)
+            };
+            return (score, Vec::new());
+        }
+        self.stats.expanded_nodes += 1;
+        self.stats.children += legal_moves.len();
+        let mut best: Option<(i32, Vec<GameMove>)> = None;
+        for game_move in legal_moves {
+            let new_board = MoveExecutor::promote_to_queen(&MoveExecutor::execute(&board, &game_move));
+            let (score, line) = self.minmax(new_board, depth - 1, current_color.opposite_color(), !maximising);
+            let is_better = match &best {
+                None => true,
+                Some((best_score, _)) if maximising => score > *best_score,
+                Some((best_score, _)) => score < *best_score,
+            };
+            if is_better {
+                let mut best_line = vec![game_move];
+                best_line.extend(line);
+                best = Some((score, best_line));
+            }
+        }
+        best.unwrap()
+    }
+}
@@ -0,0 +1,8 @@
+fn main() {
+    let address = std::env::args().nth(1).unwrap_or_else(|| "127.0.0.1:8080".to_string());
+    println!("Serving the board on http://{}", address);
+    if let Err(err) = checkers::web::serve(&address) {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+}
@@ -3,6 +3,7 @@ use std::fmt::Formatter;
 use std::ops::Index;
 use crate::{Piece, White, Black, CheckersColor};
 use crate::checkers_utils::CheckersError;
+use crate::notation::coordinates_from_square;
 use crate::col;
 use crate::col::{colored_text};
 
@@ -60,6 +61,25 @@ impl Board {
         return ret;
     }
 
+    // Same as repr, seen from Black's side when flipped.
+    pub fn repr_oriented(&self, flipped: bool) -> String {
+        if !flipped {
+            return self.repr();
+        }
+        let cells: Vec<Cell> = self.into_iter().collect();
+        let mut ret = String::from("   H  G  F  E  D  C  B  A \n");
+        for row in (0..8).rev() {
+            ret = format!("{}{} ", ret, 8 - row);
+            for column in (0..8).rev() {
+                let index = row * 8 + column;
+                ret = format!("{}{}", ret, self.get_cell_repr(&cells[index as usize], index, row));
+            }
+            ret = format!("{} {}\n", ret, 8 - row);
+        }
+        ret = format!("{}   H  G  F  E  D  C  B  A \n", ret);
+        ret
+    }
+
     fn get_cell_repr(&self, cell: &Cell, column_counter: i32, row_counter: i32) -> String {
         let mut ret = "".to_string();
         if (column_counter - row_counter).abs() % 2 == 0 {
@@ -84,10 +104,10 @@ impl Board {
 
     pub fn get_at(&self, x: usize, y: usize) -> Result<Option<Piece>, CheckersError> {
         if  x > 7 || y > 7 {
-            return Err(CheckersError::IndexOutOfBounds);
+            return Err(CheckersError::IndexOutOfBounds { x, y });
         }
         if (x + y) % 2 == 0 {
-            return Err(CheckersError::RuleError);
+            return Err(CheckersError::LightSquare { x, y });
         }
         let shift = 4 * (4 * x + y / 2);
         let value = (self._board >> shift) & self._mask as u128;
@@ -97,14 +117,14 @@ impl Board {
 
     pub fn set_at(&mut self, x: usize, y: usize, value: u8) -> Result<(), CheckersError> {
         if  x > 7 || y > 7 {
-            return Err(CheckersError::IndexOutOfBounds);
+            return Err(CheckersError::IndexOutOfBounds { x, y });
         }
         if (x + y) % 2 == 0 {
-            return Err(CheckersError::RuleError);
+            return Err(CheckersError::LightSquare { x, y });
         }
         match value {
             Self::EMPTY | Self::WHITE_PAWN | Self::WHITE_QUEEN | Self::BLACK_PAWN | Self::BLACK_QUEEN => {},
-            _ => return Err(CheckersError::PawnBinaryValueError),
+            _ => return Err(CheckersError::PawnBinaryValueError(value)),
         };
         let shift = x as u128 * 16_u128 + (y / 2) as u128 * 4_u128;
         let remove_mask = 0b1111_u128 << shift;
@@ -125,10 +145,10 @@ impl Board {
 
     pub fn is_field_excluded(&self, x: usize, y: usize) -> Result<bool, CheckersError> {
         if  x > 7 || y > 7 {
-            return Err(CheckersError::IndexOutOfBounds);
+            return Err(CheckersError::IndexOutOfBounds { x, y });
         }
         if (x + y) % 2 == 0 {
-            return Err(CheckersError::RuleError);
+            return Err(CheckersError::LightSquare { x, y });
         }
         let shift = x as u128 * 16_u128 + (y / 2) as u128 * 4_u128;
         Ok((self._board >> shift) & 0b1000 == 0b1000)
@@ -136,10 +156,10 @@ impl Board {
 
     pub fn set_field_excluded(&mut self, x: usize, y: usize) -> Result<(), CheckersError> {
         if  x > 7 || y > 7 {
-            return Err(CheckersError::IndexOutOfBounds);
+            return Err(CheckersError::IndexOutOfBounds { x, y });
         }
         if (x + y) % 2 == 0 {
-            return Err(CheckersError::RuleError);
+            return Err(CheckersError::LightSquare { x, y });
         }
 
         let shift = x as u128 * 16_u128 + (y / 2) as u128 * 4_u128;
@@ -162,6 +182,35 @@ impl Board {
         self._board
     }
 
+    // Pieces on squares 1-32: 1 and 2 for a white pawn and queen, -1 and -2 for black ones, 0 for an empty square.
+    pub fn to_array(&self) -> [i8; 32] {
+        let mut squares = [0; 32];
+        for (i, square) in squares.iter_mut().enumerate() {
+            let (x, y) = coordinates_from_square(i + 1).unwrap();
+            *square = match self.get_at(x, y) {
+                Ok(Some(Piece::Pawn(White))) => 1,
+                Ok(Some(Piece::Queen(White))) => 2,
+                Ok(Some(Piece::Pawn(Black))) => -1,
+                Ok(Some(Piece::Queen(Black))) => -2,
+                _ => 0,
+            };
+        }
+        squares
+    }
+
+    pub fn from_bits(bits: u128) -> Result<Board, CheckersError> {
+        for shift in 0..32 {
+            match ((bits >> (shift * 4)) & 0b1111) as u8 {
+                Self::EMPTY | Self::WHITE_PAWN | Self::WHITE_QUEEN | Self::BLACK_PAWN | Self::BLACK_QUEEN => {}
+                value => return Err(CheckersError::PawnBinaryValueError(value)),
+            }
+        }
+        Ok(Board {
+            _mask: 0b1111,
+            _board: bits,
+        })
+    }
+
     pub fn size(&self) -> usize {
         8
     }
@@ -199,6 +248,14 @@ impl Board {
         }
     }
 
+    pub fn try_new(pawn_rows: usize) -> Result<Board, CheckersError> {
+        if !(1..=3).contains(&pawn_rows) {
+            return Err(CheckersError::InvalidConfiguration(format!("{} rows of pawns, should be between 1 and 3", pawn_rows)));
+        }
+        Ok(Self::new(pawn_rows))
+    }
+
+    // Panics when pawn_rows is not between 1 and 3, try_new returns an error instead.
     pub fn new(pawn_rows: usize) -> Board {
         assert!(pawn_rows > 0 && pawn_rows < 4, "Invalid row number. Should be between 1 nad 3. Your input {}", pawn_rows);
         let empty_rows = 8 - 2 * pawn_rows;
@@ -4,6 +4,26 @@ use crate::board::Cell;
 
 pub trait Estimator {
     fn estimate(&self, board: Board, maximising_color: CheckersColor, check_for_endgame: bool) -> i32;
+
+    // Score of a pawn in the units of estimate, thresholds given in pawns are scaled by it.
+    fn pawn_value(&self) -> i32 {
+        1
+    }
+}
+
+pub fn game_end_score(board: Board, maximising_color: CheckersColor) -> Option<i32> {
+    if MoveExecutor::has_game_ended(board, CheckersColor::White) {
+        return match maximising_color {
+            CheckersColor::White => Some(i32::MIN),
+            CheckersColor::Black => Some(i32::MAX),
+        }
+    } else if MoveExecutor::has_game_ended(board, CheckersColor::Black) {
+        return match maximising_color {
+            CheckersColor::White => Some(i32::MAX),
+            CheckersColor::Black => Some(i32::MIN),
+        }
+    }
+    None
 }
 
 pub struct CountEstimator {
@@ -23,16 +43,8 @@ impl CountEstimator {
 impl Estimator for CountEstimator {
     fn estimate(&self, board: Board, maximising_color: CheckersColor, check_for_endgame: bool) -> i32 {
         if check_for_endgame {
-            if MoveExecutor::has_game_ended(board, CheckersColor::White) {
-                return match maximising_color {
-                    CheckersColor::White => i32::MIN,
-                    CheckersColor::Black => i32::MAX,
-                }
-            } else if MoveExecutor::has_game_ended(board, CheckersColor::Black) {
-                return match maximising_color {
-                    CheckersColor::White => i32::MAX,
-                    CheckersColor::Black => i32::MIN,
-                }
+            if let Some(score) = game_end_score(board, maximising_color) {
+                return score;
             }
         }
         let mut score = 0;
@@ -47,6 +59,13 @@ impl Estimator for CountEstimator {
                 Piece::Queen(CheckersColor::Black) => score -= self.queen_weight as i32,
             }
         }
-        score
+        match maximising_color {
+            CheckersColor::White => score,
+            CheckersColor::Black => -score,
+        }
+    }
+
+    fn pawn_value(&self) -> i32 {
+        self.pawn_weight as i32
     }
 }
\ No newline at end of file
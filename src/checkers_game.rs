@@ -1,42 +1,106 @@
 use std::cmp::min;
-use itertools::all;
-use crate::{Board, CheckersColor, Human, MoveExecutor, Piece, Player, SimpleMove};
+use std::fmt::{Display, Formatter};
+use log::{error, info};
+use crate::{Board, CheckersColor, Instant, Piece, Player};
+use crate::players::{self, Decision};
 use crate::checkers_utils::CheckersError;
-use crate::moves::{Jump, Move};
+use crate::game_state::{GameState, GameStatus};
+use crate::observers::{ConsoleObserver, GameObserver};
+use crate::moves::GameMove;
+use crate::logging::GameLog;
+use crate::notation::{fen_from_board, move_to_pdn};
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameResult {
+    Win(CheckersColor),
+    Draw,
+}
+
+impl GameResult {
+    pub fn pdn_result(&self) -> &'static str {
+        match self {
+            GameResult::Win(CheckersColor::White) => "2-0",
+            GameResult::Win(CheckersColor::Black) => "0-2",
+            GameResult::Draw => "1-1",
+        }
+    }
+}
+
+impl Display for GameResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameResult::Win(color) => write!(f, "{:?} wins", color),
+            GameResult::Draw => write!(f, "Draw"),
+        }
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SavedGame {
+    pub pawn_rows: usize,
+    pub board: Board,
+    pub side_to_move: CheckersColor,
+    pub idle_white: u8,
+    pub idle_black: u8,
+    pub random_used: u8,
+    pub moves: Vec<GameMove>,
+    pub snapshots: Vec<(Board, u8, u8)>,
+}
 
 pub struct Game<'a> {
     pub player1: &'a dyn Player,
     pub player2: &'a dyn Player,
     pub pawn_rows: usize,
     pub allow_first_random: bool,
-    pub board: Board,
-    current_color: CheckersColor,
+    pub verbose: bool,
+    #[cfg(feature = "serde")]
+    pub autosave: Option<String>,
+    // Records the moves, their timings and the searches of the bots, see GameLog.
+    pub log_file: Option<String>,
+    state: GameState,
     bot_count: u8,
     random_used: u8,
-    idle_white: u8,
-    idle_black: u8,
+    observers: Vec<&'a dyn GameObserver>,
+    console: Option<ConsoleObserver>,
+    game_log: Option<GameLog>,
 }
 
 impl <'a> Game<'a> {
+    // Panics when rows is not between 1 and 3, try_new returns an error instead.
     pub fn new(p1: &'a dyn Player, p2: &'a dyn Player, rows: usize) -> Self {
         assert!(rows > 0 && rows < 4, "Invalid row number. Should be between 1 nad 3. Your input {}", rows);
         //assert_ne!(p1.get_color(), p2.get_color(), "Players cannot have the same color!\nP1: {:?}\nP2: {:?}", p1.get_color(), p2.get_color());
-        let board = Board::new(rows);
-        let game = Self {
+        Self {
             player1: p1,
             player2: p2,
             pawn_rows: rows,
             allow_first_random: false,
-            board,
-            current_color: CheckersColor::White,
+            verbose: true,
+            #[cfg(feature = "serde")]
+            autosave: None,
+            log_file: None,
+            state: GameState::new(rows),
             bot_count: 0,
             random_used: 0,
-            idle_black: 0,
-            idle_white: 0,
-        };
-        game
+            observers: Vec::new(),
+            console: None,
+            game_log: None,
+        }
+    }
+
+    pub fn try_new(p1: &'a dyn Player, p2: &'a dyn Player, rows: usize) -> Result<Self, CheckersError> {
+        if !(1..=3).contains(&rows) {
+            return Err(CheckersError::InvalidConfiguration(format!("{} rows of pawns, should be between 1 and 3", rows)));
+        }
+        if p1.get_color() == p2.get_color() {
+            return Err(CheckersError::InvalidConfiguration(format!("both players play {:?}", p1.get_color())));
+        }
+        Ok(Self::new(p1, p2, rows))
     }
 
+    // Panics on a bot count other than 1 or 2 and on the rows new panics on, see try_new_with_bots.
     pub fn new_with_bots(p1: &'a mut dyn Player, p2: &'a mut dyn Player, bot_count: u8, rows: usize) -> Self {
         assert!(bot_count < 3 && bot_count > 0, "Bot count should be between 1 and 2. Your input: {}", bot_count);
         let mut game = Self::new(p1, p2, rows);
@@ -44,93 +108,202 @@ impl <'a> Game<'a> {
         game
     }
 
-    pub fn play(&mut self) {
-        while !MoveExecutor::has_game_ended(self.board, self.current_color) &&
-            self.idle_black < 8 && self.idle_white < 8 {
-            println!("{}", self.board.repr());
-            self.one_move()
+    pub fn try_new_with_bots(p1: &'a mut dyn Player, p2: &'a mut dyn Player, bot_count: u8, rows: usize) -> Result<Self, CheckersError> {
+        if !(1..=2).contains(&bot_count) {
+            return Err(CheckersError::InvalidConfiguration(format!("{} bots, should be 1 or 2", bot_count)));
         }
-        self.switch_player();
-        println!("{}", self.board.repr());
-        println!("Player {} win!", self.current_player().get_name());
+        let mut game = Self::try_new(p1, p2, rows)?;
+        game.bot_count = bot_count;
+        Ok(game)
+    }
+
+    pub fn add_observer(&mut self, observer: &'a dyn GameObserver) {
+        self.observers.push(observer);
     }
 
-    fn switch_player(&mut self) {
-        match self.current_color {
-            CheckersColor::White => {
-                self.current_color = CheckersColor::Black;
+    pub fn play(&mut self) -> GameResult {
+        self.console = if self.verbose {
+            Some(ConsoleObserver::new(self.player1.get_name(), self.player2.get_name()))
+        } else {
+            None
+        };
+        self.game_log = self.log_file.as_ref().and_then(|path| {
+            GameLog::create(path, self.player1.get_name(), self.player2.get_name(), self.state.history().len())
+                .map_err(|err| error!("Could not write the game log to {}: {}", path, err))
+                .ok()
+        });
+        let board = self.state.board();
+        info!("Game started: {} (White) against {} (Black) from {}", self.player1.get_name(), self.player2.get_name(),
+              fen_from_board(&board, self.state.side_to_move()));
+        self.notify(|observer| observer.on_game_start(&board, self.state.side_to_move()));
+        let result = loop {
+            match self.state.status() {
+                GameStatus::InProgress => self.one_move(),
+                GameStatus::Finished(result) => break result,
             }
-            CheckersColor::Black => {
-                self.current_color = CheckersColor::White;
+            #[cfg(feature = "serde")]
+            self.write_autosave();
+        };
+        let board = self.state.board();
+        info!("Game over: {} after {} plies", result, self.state.history().len());
+        self.notify(|observer| observer.on_game_end(&board, result));
+        self.game_log = None;
+        result
+    }
+
+    // Replaces the public board field, which the game state now keeps.
+    pub fn board(&self) -> Board {
+        self.state.board()
+    }
+
+    // Writing the old board field set up a position, this starts the game from the board instead.
+    #[deprecated(note = "set up a position with load or a GameState")]
+    pub fn set_board(&mut self, board: Board) {
+        self.state = GameState::from_position(board, self.state.side_to_move());
+    }
+
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    pub fn moves(&self) -> &[GameMove] {
+        self.state.history()
+    }
+
+    pub fn save(&self) -> SavedGame {
+        let (idle_white, idle_black) = self.state.idle_counters();
+        SavedGame {
+            pawn_rows: self.pawn_rows,
+            board: self.state.board(),
+            side_to_move: self.state.side_to_move(),
+            idle_white,
+            idle_black,
+            random_used: self.random_used,
+            moves: self.state.history().to_vec(),
+            snapshots: self.state.snapshots().to_vec(),
+        }
+    }
+
+    // The saved game is replayed from the position before its first move, the snapshots, the board
+    // and the side to move have to match the replayed positions.
+    pub fn load(&mut self, saved: SavedGame) -> Result<(), CheckersError> {
+        GameState::try_new(saved.pawn_rows)?;
+        if saved.snapshots.len() != saved.moves.len() {
+            return Err(CheckersError::InvalidConfiguration(format!("the saved game has {} moves but {} snapshots",
+                saved.moves.len(), saved.snapshots.len())));
+        }
+        let first_color = if saved.moves.len().is_multiple_of(2) { saved.side_to_move } else { saved.side_to_move.opposite_color() };
+        let (board, idle_white, idle_black) = saved.snapshots.first().copied()
+            .unwrap_or((saved.board, saved.idle_white, saved.idle_black));
+        let mut state = GameState::restore(board, first_color, (idle_white, idle_black), Vec::new(), Vec::new());
+        for game_move in &saved.moves {
+            state.apply(game_move)?;
+        }
+        let same = |a: &(Board, u8, u8), b: &(Board, u8, u8)| a.0.get_board() == b.0.get_board() && (a.1, a.2) == (b.1, b.2);
+        let (replayed_white, replayed_black) = state.idle_counters();
+        let matches = state.snapshots().iter().zip(&saved.snapshots).all(|(a, b)| same(a, b))
+            && same(&(state.board(), replayed_white, replayed_black), &(saved.board, saved.idle_white, saved.idle_black))
+            && state.side_to_move() == saved.side_to_move;
+        if !matches {
+            return Err(CheckersError::InvalidConfiguration("the positions of the saved game do not match its moves".to_string()));
+        }
+        self.pawn_rows = saved.pawn_rows;
+        self.random_used = saved.random_used;
+        self.state = state;
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    fn write_autosave(&self) {
+        if let Some(path) = &self.autosave {
+            if let Err(err) = std::fs::write(path, self.save().to_json()) {
+                error!("Could not save the game to {}: {}", path, err);
             }
         }
     }
 
-    fn current_player(&self) -> &dyn Player  {
-        // if self.current_color == self.player1.get_color() {
-        //     self.player1
-        // } else {
-        //     self.player2
-        // }
-        match self.current_color {
+    fn player(&self, color: CheckersColor) -> &dyn Player {
+        match color {
             CheckersColor::White => self.player1,
             CheckersColor::Black => self.player2,
         }
     }
 
-    fn one_move(&mut self) {
-        let pieces = MoveExecutor::get_pieces(&self.board, self.current_color);
-        let (cap_pawns, cap_queens) = MoveExecutor::get_capturing_pieces(&self.board, &pieces, self.current_color);
-        let (mov_pawns, mov_queens) = MoveExecutor::get_moving_pieces(&self.board, &pieces, self.current_color);
-        if !cap_pawns.is_empty() || !cap_queens.is_empty() {
-            self.do_capture(&cap_pawns, &cap_queens);
-        } else {
-            self.do_move(&mov_pawns, &mov_queens)
+    fn notify<F: Fn(&dyn GameObserver)>(&self, event: F) {
+        if let Some(console) = &self.console {
+            event(console);
+        }
+        if let Some(game_log) = &self.game_log {
+            event(game_log);
+        }
+        for &observer in [self.player1.observer(), self.player2.observer()].iter().flatten() {
+            event(observer);
+        }
+        for &observer in &self.observers {
+            event(observer);
         }
-        self.board = MoveExecutor::promote_to_queen(&self.board);
-        self.switch_player();
     }
 
-    fn do_capture(&mut self, cap_pawns: &[(usize, usize)], cap_queens: &[(usize, usize)]) {
-        let pawn_captures = MoveExecutor::get_possible_pawn_captures(&self.board, cap_pawns, self.current_color);
-        let queen_captures = MoveExecutor::get_possible_queen_captures(&self.board, cap_queens, self.current_color);
-        let longest_captures = MoveExecutor::get_longest_captures(
-            &mut pawn_captures.iter().map(|v| v).collect(),
-            &mut queen_captures.iter().map(|v| v).collect());
-        let board_copy = self.board.clone();
-        let pos = if self.allow_first_random && (self.random_used < self.bot_count) {
-            self.random_used = min(self.random_used + 1, self.bot_count);
-            self.current_player().capture(&longest_captures, board_copy, true)
-        } else {
-            self.current_player().capture(&longest_captures, board_copy, false)
+    fn one_move(&mut self) {
+        let color = self.state.side_to_move();
+        let board = self.state.board();
+        let legal_moves = self.state.legal_moves();
+        let start = Instant::now();
+        let game_move = loop {
+            match self.ask_player(board, &legal_moves) {
+                Decision::Move(index) => {
+                    if let Some(stats) = self.player(color).last_search_stats() {
+                        self.notify(|observer| observer.on_search(color, &stats));
+                    }
+                    break legal_moves[index].clone();
+                }
+                Decision::Undo if self.state.history().len() >= 2 => {
+                    self.state.undo();
+                    self.state.undo();
+                    info!("{:?} took back the last two plies", color);
+                    let board = self.state.board();
+                    self.notify(|observer| observer.on_undo(&board, color));
+                    return;
+                }
+                Decision::Undo => self.notify(|observer| observer.on_undo_rejected(color)),
+                Decision::Resign => {
+                    info!("{:?} resigned", color);
+                    self.state.resign(color);
+                    return;
+                }
+                Decision::OfferDraw => {
+                    let opponent = self.player(color.opposite_color());
+                    if opponent.accept_draw(board) {
+                        info!("{:?} offered a draw, {} accepted", color, opponent.get_name());
+                        self.state.agree_draw();
+                        return;
+                    }
+                    info!("{:?} offered a draw, {} declined", color, opponent.get_name());
+                    self.notify(|observer| observer.on_draw_declined(color));
+                }
+            }
         };
-        let player_choice = longest_captures[pos];
-        self.board = MoveExecutor::execute_capture(&self.board, &player_choice);
+        info!("Ply {}: {:?} {} played {} in {:?}", self.state.history().len() + 1, color, self.player(color).get_name(),
+              move_to_pdn(&game_move), start.elapsed());
+        self.state.apply(&game_move).expect("the move was taken from the legal move list");
+        let board_after = self.state.board();
+        self.notify(|observer| observer.on_move(color, &board, &game_move, &board_after));
+        if game_move.is_capture() {
+            let captured = game_move.captured();
+            self.notify(|observer| observer.on_capture(color, &game_move, &captured));
+        }
+        let (x_start, y_start) = game_move.start_pair();
+        let (x, y) = game_move.end_pair();
+        if let (Ok(Some(Piece::Pawn(_))), Ok(Some(Piece::Queen(_)))) = (board.get_at(x_start, y_start), board_after.get_at(x, y)) {
+            self.notify(|observer| observer.on_promotion(color, x, y));
+        }
     }
 
-    fn do_move(&mut self, mov_paws: &[(usize, usize)], mov_queens: &[(usize, usize)]) {
-        let mut pawn_moves = MoveExecutor::get_possible_pawn_moves(&self.board, mov_paws, self.current_color);
-        let mut queen_moves = MoveExecutor::get_possible_queen_moves(&self.board, mov_queens);
-        pawn_moves.append(&mut queen_moves);
-        let all_moves = pawn_moves;
-        let board_copy = self.board.clone();
-        let pos = if self.allow_first_random && self.random_used < self.bot_count {
+    fn ask_player(&mut self, board: Board, legal_moves: &[GameMove]) -> Decision {
+        let allow_random = self.allow_first_random && self.random_used < self.bot_count;
+        if allow_random {
             self.random_used = min(self.random_used + 1, self.bot_count);
-            self.current_player().move_piece(&all_moves, board_copy, true)
-        } else {
-            self.current_player().move_piece(&all_moves, board_copy, false)
-        };
-        let player_choice = all_moves[pos];
-        self.check_for_idle(player_choice);
-        self.board = MoveExecutor::execute_move(self.board, player_choice)
-    }
-
-    fn check_for_idle(&mut self, mov: SimpleMove) {
-        match self.board.get_at(mov.x_start, mov.y_start).unwrap().unwrap() {
-            Piece::Queen(CheckersColor::Black) => self.idle_black += 1,
-            Piece::Queen(CheckersColor::White) => self.idle_white += 1,
-            Piece::Pawn(CheckersColor::Black) => self.idle_black = 0,
-            Piece::Pawn(CheckersColor::White) => self.idle_white = 0,
-        };
+        }
+        players::decide(self.player(self.state.side_to_move()), board, legal_moves, allow_random)
     }
-}
\ No newline at end of file
+}
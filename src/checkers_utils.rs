@@ -1,5 +1,6 @@
 use crate::{Board, CheckersColor, Piece};
-use crate::moves::{Jump, Move, SimpleMove};
+use crate::moves::{GameMove, Jump, Move, SimpleMove};
+pub use crate::errors::CheckersError;
 
 pub fn is_in_bounds(x: i32, y: i32) -> bool {
     x >= 0 && x < 8 && y >= 0 && y < 8
@@ -9,7 +10,21 @@ pub fn alias_from_coordinates(x: usize, y: usize) -> Result<String, CheckersErro
     if is_in_bounds(x as i32, y as i32) {
         return Ok(format!("{}{}", "ABCDEFGH".as_bytes()[y] as char, 8 - x))
     }
-    Err(CheckersError::IndexOutOfBounds)
+    Err(CheckersError::IndexOutOfBounds { x, y })
+}
+
+pub fn coordinates_from_alias(alias: &str) -> Result<(usize, usize), CheckersError> {
+    let alias = alias.trim().to_uppercase();
+    let mut chars = alias.chars();
+    let y = match chars.next() {
+        Some(column @ 'A'..='H') => column as usize - 'A' as usize,
+        _ => return Err(CheckersError::notation(&alias, "the column should be a letter from A to H")),
+    };
+    let x = match chars.as_str().parse::<usize>() {
+        Ok(row @ 1..=8) => 8 - row,
+        _ => return Err(CheckersError::notation(&alias, "the row should be a number from 1 to 8")),
+    };
+    Ok((x, y))
 }
 
 
@@ -141,7 +156,7 @@ impl MoveExecutor {
         paths
     }
 
-    fn get_pawn_capture_path(board: &Board, pawn: (usize, usize), color: CheckersColor, acc: &mut Vec<Jump>, solutions: &mut Vec<Vec<Jump>>) {
+    fn get_pawn_capture_path(board: &Board, pawn: (usize, usize), color: CheckersColor, acc: &mut [Jump], solutions: &mut Vec<Vec<Jump>>) {
         if Self::can_pawn_capture(board, pawn, color) {
             let directions = Self::get_pawn_capture_directions(board, pawn, color);
             for (dx, dy) in directions {
@@ -154,10 +169,11 @@ impl MoveExecutor {
                     x_capture: (x_start as i32 + dx) as usize,
                     y_capture: (y_start as i32 + dy) as usize,
                 };
-                acc.push(jump);
                 let mut board_copy = board.clone();
                 let _ = board_copy.set_field_excluded(jump.x_capture, jump.y_capture);
-                Self::get_pawn_capture_path(&board_copy, jump.end_pair(), color, &mut acc.to_vec(), solutions)
+                let mut acc_copy = acc.to_vec();
+                acc_copy.push(jump);
+                Self::get_pawn_capture_path(&board_copy, jump.end_pair(), color, &mut acc_copy, solutions)
             }
         } else {
             solutions.push(acc.to_vec());
@@ -331,6 +347,69 @@ impl MoveExecutor {
         max_path
     }
 
+    pub fn get_legal_moves(board: &Board, color: CheckersColor) -> Vec<GameMove> {
+        let pieces = Self::get_pieces(board, color);
+        let (cap_pawns, cap_queens) = Self::get_capturing_pieces(board, &pieces, color);
+        if !cap_pawns.is_empty() || !cap_queens.is_empty() {
+            let pawn_captures = Self::get_possible_pawn_captures(board, &cap_pawns, color);
+            let queen_captures = Self::get_possible_queen_captures(board, &cap_queens, color);
+            let longest_captures = Self::get_longest_captures(
+                &mut pawn_captures.iter().collect(),
+                &mut queen_captures.iter().collect());
+            return longest_captures.into_iter().map(|capture| GameMove::Capture(capture.to_vec())).collect();
+        }
+        Self::get_all_moves(board, color).into_iter().map(GameMove::Simple).collect()
+    }
+
+    pub fn execute(board: &Board, game_move: &GameMove) -> Board {
+        match game_move {
+            GameMove::Simple(mov) => Self::execute_move(*board, *mov),
+            GameMove::Capture(jumps) => Self::execute_capture(board, jumps),
+        }
+    }
+
+    pub fn execute_checked(board: &Board, color: CheckersColor, game_move: &GameMove) -> Result<Board, CheckersError> {
+        if let GameMove::Capture(jumps) = game_move {
+            if jumps.is_empty() {
+                return Err(CheckersError::IllegalMove("empty capture".to_string()));
+            }
+        }
+        let (x, y) = game_move.start_pair();
+        match board.get_at(x, y)? {
+            None => return Err(CheckersError::NoPiece { x, y }),
+            Some(piece) if piece.color() != color => return Err(CheckersError::WrongColor { x, y }),
+            Some(_) => {}
+        }
+        let legal_moves = Self::get_legal_moves(board, color);
+        if legal_moves.contains(game_move) {
+            return Ok(Self::execute(board, game_move));
+        }
+        if !game_move.is_capture() && legal_moves.iter().any(|m| m.is_capture()) {
+            return Err(CheckersError::CaptureRequired(game_move.to_string()));
+        }
+        Err(CheckersError::IllegalMove(game_move.to_string()))
+    }
+
+    pub fn execute_move_checked(board: &Board, color: CheckersColor, one_move: SimpleMove) -> Result<Board, CheckersError> {
+        Self::execute_checked(board, color, &GameMove::Simple(one_move))
+    }
+
+    pub fn execute_capture_checked(board: &Board, color: CheckersColor, capture: &[Jump]) -> Result<Board, CheckersError> {
+        Self::execute_checked(board, color, &GameMove::Capture(capture.to_vec()))
+    }
+
+    pub fn perft(board: &Board, color: CheckersColor, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let mut nodes = 0;
+        for game_move in Self::get_legal_moves(board, color) {
+            let new_board = Self::promote_to_queen(&Self::execute(board, &game_move));
+            nodes += Self::perft(&new_board, color.opposite_color(), depth - 1);
+        }
+        nodes
+    }
+
     // === checks ===
     fn can_pawn_capture(board: &Board, pawn: (usize, usize), current_color: CheckersColor) -> bool {
         for direction in Self::DIRECTIONS {
@@ -471,10 +550,3 @@ impl MoveExecutor {
         true
     }
 }
-
-#[derive(PartialEq, Clone, Copy, Debug)]
-pub enum CheckersError {
-    IndexOutOfBounds,
-    RuleError,
-    PawnBinaryValueError,
-}
\ No newline at end of file
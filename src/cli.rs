@@ -0,0 +1,891 @@
+use std::collections::HashMap;
+use std::fs;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use checkers::{Board, CheckersColor, MoveExecutor};
+use checkers::analysis::{self, format_score};
+use checkers::statistics::SearchStats;
+use checkers::logging::{self, LogFilter};
+use checkers::board_estimators::{CountEstimator, Estimator};
+use checkers::mcts::{MctsBot, PolicyValueNet};
+use checkers::neural::{self, NeuralEstimator};
+use checkers::self_play::{self, ReplayBuffer, SelfPlayConfig};
+use checkers::training_data::{self, ExportConfig, PositionRecorder};
+use checkers::review::{self, ReviewThresholds};
+use checkers::replay::Replay;
+use checkers::puzzles::{self, Puzzle, PuzzleConfig, PuzzleScores, PuzzleSession, PuzzleStep};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use checkers::checkers_game::{Game, GameResult};
+use checkers::diagram::Diagram;
+use checkers::moves::GameMove;
+use checkers::notation::{self, board_from_fen, fen_from_board, PdnGame};
+use checkers::players::{self, Decision, DummyBot, Human, MinMaxBot, Player};
+use checkers::tui::TuiHuman;
+
+pub const USAGE: &str = "Usage: checkers <command> [options]
+
+Commands:
+  play                     play a game (default command)
+      --white <kind>           human, tui, dummy, minmax or mcts (default: human)
+      --black <kind>           human, tui, dummy, minmax or mcts (default: minmax)
+      --white-name <name>      name of the white player
+      --black-name <name>      name of the black player
+      --depth <n>              search depth of minmax bots, 1-12 (default: 5)
+      --white-depth <n>        search depth of the white bot
+      --black-depth <n>        search depth of the black bot
+      --rows <n>               rows of pawns per player, 1-3 (default: 3)
+      --estimator <name>       board estimator: count or neural (default: count)
+      --pawn-weight <n>        pawn weight of the count estimator (default: 1)
+      --queen-weight <n>       queen weight of the count estimator (default: 3)
+      --weights <file>         network file of the neural estimator
+      --net <file>             policy/value network of mcts bots
+      --simulations <n>        searched positions per move of mcts bots (default: 200)
+      --random-openings        bots play their first move at random
+      --hint-depth <n>         search depth of hints for human players, 1-10 (default: 4)
+      --blunder-check <n>      warn a human player before a move that loses more than
+                               n pawns against the best move
+      --pdn <file>             save the finished game to a PDN file
+      --review <file>          review the finished game and write it as annotated PDN
+      --review-depth <n>       search depth of the review, 1-10 (default: 4)
+      --log <file>             log the moves, their timings and the bot searches to a file
+      --replay                 step through the finished game as with the replay command
+      --save <file>            save the game as JSON after every move (serde feature)
+      --resume <file>          continue a game saved with --save (serde feature)
+  analyse <FEN>            print every legal move with its score, the best line and search statistics
+      --depth, --estimator, --pawn-weight, --queen-weight, --weights as above
+      --time <ms>              search deeper and deeper up to --depth until the time is up
+      --stats <file>           write the search statistics as JSON (serde feature)
+  perft                    count move generator leaf nodes
+      --fen <FEN>              start position (default: initial position)
+      --rows <n>               rows of pawns for the initial position (default: 3)
+      --depth <n>              maximal depth (default: 5)
+  match                    play a series of bot games
+      --games <n>              number of games (default: 10)
+      --log-dir <dir>          write a log of every game to game-<n>.log in this directory
+      --white, --black, --depth, --white-depth, --black-depth, --rows,
+      --estimator, --pawn-weight, --queen-weight, --weights, --net,
+      --simulations as in play (default kind: minmax)
+  train                    fit the neural estimator on self-play games of minmax bots
+      --games <n>              number of self-play games (default: 50)
+      --depth <n>              search depth of the self-play bots (default: 3)
+      --random-plies <n>       random moves at the start of every game (default: 6)
+      --epochs <n>             passes over the positions (default: 20)
+      --hidden <sizes>         comma separated hidden layer sizes (default: 32)
+      --learning-rate <x>      step size of gradient descent (default: 0.01)
+      --seed <n>               random seed (default: 0)
+      --weights <file>         continue training an existing network
+      --output <file>          where to save the network (default: neural.txt)
+  selfplay                 train the policy/value network of the mcts bot on its own games
+      --rows <n>               rows of pawns per player, 1-3 (default: 2)
+      --iterations <n>         rounds of self-play and training (default: 10)
+      --games <n>              self-play games per iteration (default: 20)
+      --simulations <n>        searched positions per move (default: 100)
+      --steps <n>              training batches per iteration (default: 100)
+      --batch <n>              positions per batch (default: 32)
+      --hidden <sizes>         comma separated hidden layer sizes (default: 64)
+      --learning-rate <x>      step size of gradient descent (default: 0.01)
+      --capacity <n>           positions kept in the replay buffer (default: 20000)
+      --buffer <file>          load the replay buffer from and save it to this file
+      --seed <n>               random seed (default: 0)
+      --net <file>             continue training an existing network
+      --output <file>          where to save the network (default: policy.txt)
+  export                   write labelled positions of bot games to a CSV file
+      --games <n>              number of games (default: 10)
+      --white, --black, --depth, --white-depth, --black-depth, --rows,
+      --estimator, --pawn-weight, --queen-weight, --weights, --net,
+      --simulations as in play (default kind: minmax)
+      --label-depth <n>        search depth of the score and best move labels (default: 4)
+      --skip-plies <n>         positions left out at the start of every game (default: 4)
+      --sample-rate <x>        probability of keeping a position, 0-1 (default: 1)
+      --seed <n>               random seed of the sampling (default: 0)
+      --output <file>          where to write the records (default: positions.csv)
+  puzzles [file.pdn]       find capture combinations in recorded games, or in bot games
+                           without a file, and write them as PDN with the solution
+      --games <n>              number of bot games (default: 10)
+      --white, --black, --depth, --white-depth, --black-depth, --rows,
+      --estimator, --pawn-weight, --queen-weight, --weights, --net,
+      --simulations as in play (default kind: minmax)
+      --puzzle-depth <n>       search depth of the combinations, 2-8 (default: 5)
+      --min-gain <n>           pawns the combination has to win (default: 1)
+      --output <file>          where to write the puzzles (default: puzzles.pdn)
+  solve <file.pdn>         solve the puzzles of a file written by the puzzles command
+      --name <name>            your name (default: Human)
+      --depth <n>              search depth of the opponent's replies outside the solution (default: 6)
+      --start <n>              number of the first puzzle (default: 1)
+      --unsolved               skip the puzzles you have already solved
+      --scores <file>          where your results are kept (default: puzzle-scores.txt)
+  review <file.pdn>        search every position of a recorded game, mark inaccuracies, mistakes
+                           and blunders and write the game with comments and better lines
+      --game <n>               game number inside the file (default: 1)
+      --review-depth <n>       search depth, 1-10 (default: 4)
+      --estimator, --pawn-weight, --queen-weight, --weights as in play
+      --output <file>          where to write the annotated game (default: review.pdn)
+  replay <file.pdn>        step through a recorded game, forward and back
+      --game <n>               game number inside the file (default: 1)
+      --flip                   show the board from Black's side
+      --all                    print every position at once instead
+  diagram [FEN]            draw an SVG diagram (PNG with the png feature)
+      --pdn <file>             take the position from a recorded game instead of a FEN
+      --game <n>               game number inside the PDN file (default: 1)
+      --ply <n>                position after n plies, the last one drawn as an arrow
+                               (default: end of the game)
+      --move <move>            draw an arrow for a legal move, e.g. 22-18 or c3-d4
+      --highlight <squares>    comma separated squares to highlight, e.g. c3,22
+      --size <n>               size of a square in pixels (default: 60)
+      --flip                   show the board from Black's side
+      --no-coordinates         do not draw the coordinates
+      --output <file>          write to a .svg or .png file instead of stdout
+  help                     print this message
+
+Environment:
+  CHECKERS_LOG             log filter of the console, e.g. info or warn,checkers::players=debug
+                           (default: warn)";
+
+struct Options {
+    positional: Vec<String>,
+    values: HashMap<String, String>,
+    flags: Vec<String>,
+}
+
+impl Options {
+    fn parse(args: &[String], allowed_values: &[&str], allowed_flags: &[&str]) -> Result<Self, String> {
+        let mut options = Options {
+            positional: Vec::new(),
+            values: HashMap::new(),
+            flags: Vec::new(),
+        };
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.strip_prefix("--") {
+                Some(name) if allowed_flags.contains(&name) => options.flags.push(name.to_string()),
+                Some(name) if allowed_values.contains(&name) => {
+                    let value = iter.next().ok_or(format!("Option --{} requires a value.", name))?;
+                    options.values.insert(name.to_string(), value.clone());
+                }
+                Some(name) => return Err(format!("Unknown option --{}.", name)),
+                None => options.positional.push(arg.clone()),
+            }
+        }
+        Ok(options)
+    }
+
+    fn get<T: FromStr>(&self, name: &str, default: T) -> Result<T, String> {
+        match self.values.get(name) {
+            Some(value) => value.parse().map_err(|_| format!("Invalid value '{}' for --{}.", value, name)),
+            None => Ok(default),
+        }
+    }
+
+    fn get_in_range(&self, name: &str, default: usize, min: usize, max: usize) -> Result<usize, String> {
+        let value = self.get(name, default)?;
+        if value < min || value > max {
+            return Err(format!("Invalid value '{}' for --{}. Should be between {} and {}.", value, name, min, max));
+        }
+        Ok(value)
+    }
+
+    fn text(&self, name: &str, default: &str) -> String {
+        self.values.get(name).cloned().unwrap_or_else(|| default.to_string())
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|flag| flag == name)
+    }
+}
+
+const PLAYER_OPTIONS: [&str; 14] = ["white", "black", "white-name", "black-name", "depth", "white-depth",
+    "black-depth", "rows", "estimator", "pawn-weight", "queen-weight", "weights", "net", "simulations"];
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let filter = std::env::var("CHECKERS_LOG").unwrap_or_else(|_| logging::DEFAULT_FILTER.to_string());
+    logging::init(LogFilter::parse(&filter).map_err(|err| format!("Invalid CHECKERS_LOG: {}.", err))?);
+    let (command, rest) = match args.split_first() {
+        Some((command, rest)) if !command.starts_with("--") => (command.as_str(), rest),
+        _ => ("play", args),
+    };
+    match command {
+        "play" => {
+            let allowed: Vec<&str> = PLAYER_OPTIONS.iter().copied()
+                .chain(["pdn", "review", "review-depth", "save", "resume", "hint-depth", "blunder-check", "log"]).collect();
+            play(&Options::parse(rest, &allowed, &["random-openings", "replay"])?)
+        }
+        "analyse" | "analyze" => analyse(&Options::parse(rest, &["depth", "estimator", "pawn-weight", "queen-weight", "weights",
+            "time", "stats"], &[])?),
+        "perft" => perft(&Options::parse(rest, &["fen", "rows", "depth"], &[])?),
+        "match" => {
+            let allowed: Vec<&str> = PLAYER_OPTIONS.iter().copied().chain(["games", "log-dir"]).collect();
+            play_match(&Options::parse(rest, &allowed, &[])?)
+        }
+        "train" => train(&Options::parse(rest, &["games", "depth", "random-plies", "epochs", "hidden", "learning-rate",
+            "seed", "weights", "output"], &[])?),
+        "selfplay" => train_mcts(&Options::parse(rest, &["rows", "iterations", "games", "simulations", "steps", "batch",
+            "hidden", "learning-rate", "capacity", "buffer", "seed", "net", "output"], &[])?),
+        "export" => {
+            let allowed: Vec<&str> = PLAYER_OPTIONS.iter().copied()
+                .chain(["games", "label-depth", "skip-plies", "sample-rate", "seed", "output"]).collect();
+            export(&Options::parse(rest, &allowed, &[])?)
+        }
+        "puzzles" => {
+            let allowed: Vec<&str> = PLAYER_OPTIONS.iter().copied()
+                .chain(["games", "puzzle-depth", "min-gain", "output"]).collect();
+            find_puzzles(&Options::parse(rest, &allowed, &[])?)
+        }
+        "solve" => solve(&Options::parse(rest, &["name", "depth", "start", "scores"], &["unsolved"])?),
+        "review" => review_pdn(&Options::parse(rest, &["game", "review-depth", "estimator", "pawn-weight", "queen-weight",
+            "weights", "output"], &[])?),
+        "replay" => replay(&Options::parse(rest, &["game"], &["flip", "all"])?),
+        "diagram" => diagram(&Options::parse(rest, &["pdn", "game", "ply", "move", "highlight", "size", "output"],
+            &["flip", "no-coordinates"])?),
+        "help" | "-h" | "--help" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        other => Err(format!("Unknown command '{}'.\n\n{}", other, USAGE)),
+    }
+}
+
+fn estimator(options: &Options) -> Result<Box<dyn Estimator>, String> {
+    match options.text("estimator", "count").as_str() {
+        "count" => Ok(Box::new(CountEstimator::new(options.get("pawn-weight", 1)?, options.get("queen-weight", 3)?))),
+        "neural" => {
+            let path = options.values.get("weights").ok_or("The neural estimator needs --weights <file>.")?;
+            let network = NeuralEstimator::load(path).map_err(|err| format!("Could not load {}: {}.", path, err))?;
+            Ok(Box::new(network))
+        }
+        other => Err(format!("Unknown estimator '{}'. Available estimators: count, neural.", other)),
+    }
+}
+
+fn create_player<'a>(options: &Options, color: CheckersColor, default_kind: &str, estimator: &'a dyn Estimator) -> Result<Box<dyn Player + 'a>, String> {
+    let prefix = match color {
+        CheckersColor::White => "white",
+        CheckersColor::Black => "black",
+    };
+    let kind = options.text(prefix, default_kind);
+    let name_option = format!("{}-name", prefix);
+    let depth = options.get_in_range("depth", 5, 1, 12)?;
+    let depth = options.get_in_range(&format!("{}-depth", prefix), depth, 1, 12)?;
+    let player: Box<dyn Player> = match kind.as_str() {
+        "human" => {
+            let mut human = Human::new(&options.text(&name_option, "Human"), color);
+            human.set_hint_depth(options.get_in_range("hint-depth", 4, 1, 10)?);
+            human.set_estimator(estimator);
+            if options.values.contains_key("blunder-check") {
+                human.set_blunder_check(Some(options.get("blunder-check", 0)?));
+            }
+            Box::new(human)
+        }
+        "tui" => Box::new(TuiHuman::new(&options.text(&name_option, "Human"), color)),
+        "dummy" => Box::new(DummyBot::new(&options.text(&name_option, "Dummy"), color)),
+        "minmax" => {
+            Box::new(MinMaxBot::new(&options.text(&name_option, "MinMax"), color, depth, estimator))
+        }
+        "mcts" => {
+            let path = options.values.get("net").ok_or("The mcts bot needs --net <file>.")?;
+            let net = PolicyValueNet::load(path).map_err(|err| format!("Could not load {}: {}.", path, err))?;
+            let simulations = options.get_in_range("simulations", 200, 1, 1000000)?;
+            Box::new(MctsBot::new(&options.text(&name_option, "MCTS"), color, net, simulations))
+        }
+        other => return Err(format!("Unknown player kind '{}' for --{}. Available kinds: human, tui, dummy, minmax, mcts.", other, prefix)),
+    };
+    Ok(player)
+}
+
+fn bot_count(options: &Options, default_white: &str, default_black: &str) -> u8 {
+    let is_bot = |kind: String| kind != "human" && kind != "tui";
+    let white_bot = is_bot(options.text("white", default_white));
+    let black_bot = is_bot(options.text("black", default_black));
+    white_bot as u8 + black_bot as u8
+}
+
+fn play(options: &Options) -> Result<(), String> {
+    if let Some(arg) = options.positional.first() {
+        return Err(format!("Unexpected argument '{}'.", arg));
+    }
+    let rows = options.get_in_range("rows", 3, 1, 3)?;
+    let estimator = estimator(options)?;
+    let uses_tui = options.text("white", "human") == "tui" || options.text("black", "minmax") == "tui";
+    let mut white = create_player(options, CheckersColor::White, "human", estimator.as_ref())?;
+    let mut black = create_player(options, CheckersColor::Black, "minmax", estimator.as_ref())?;
+    let bots = bot_count(options, "human", "minmax");
+    let mut game = if bots > 0 {
+        Game::new_with_bots(white.as_mut(), black.as_mut(), bots, rows)
+    } else {
+        Game::new(white.as_ref(), black.as_ref(), rows)
+    };
+    game.allow_first_random = options.flag("random-openings");
+    game.verbose = !uses_tui;
+    game.log_file = options.values.get("log").cloned();
+    setup_saving(options, &mut game)?;
+    let result = game.play();
+    let moves = game.moves().to_vec();
+    let final_board = game.board();
+    let rows = game.pawn_rows;
+    let replay = Replay::from_game(&game);
+    drop(game);
+    let tags = [("White".to_string(), white.get_name().clone()), ("Black".to_string(), black.get_name().clone())];
+    if uses_tui {
+        let winner = match result {
+            GameResult::Win(CheckersColor::White) => Some(white.get_name().clone()),
+            GameResult::Win(CheckersColor::Black) => Some(black.get_name().clone()),
+            GameResult::Draw => None,
+        };
+        drop(white);
+        drop(black);
+        println!("{}", final_board.repr());
+        match winner {
+            Some(name) => println!("Player {} win!", name),
+            None => println!("Draw!"),
+        }
+    }
+    if let Some(path) = options.values.get("pdn") {
+        let start = if rows == 3 { None } else { Some(fen_from_board(&Board::new(rows), CheckersColor::White)) };
+        let pdn = PdnGame::new(start, &moves, result.pdn_result());
+        fs::write(path, pdn.to_pdn()).map_err(|err| format!("Could not write {}: {}", path, err))?;
+    }
+    if let Some(path) = options.values.get("review") {
+        write_review(options, Board::new(rows), CheckersColor::White, &moves, &tags, result.pdn_result(), path, estimator.as_ref())?;
+    }
+    if options.flag("replay") {
+        let mut replay = replay.map_err(|err| format!("Could not replay the game: {}.", err))?;
+        view_replay(&mut replay);
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn write_review(options: &Options, board: Board, color: CheckersColor, moves: &[GameMove], tags: &[(String, String)],
+                result: &str, path: &str, estimator: &dyn Estimator) -> Result<(), String> {
+    let depth = options.get_in_range("review-depth", 4, 1, 10)?;
+    let game_review = review::review_game(board, color, moves, depth, estimator, &ReviewThresholds::default())
+        .map_err(|err| format!("Could not review the game: {}.", err))?;
+    fs::write(path, game_review.to_pdn(tags, result)).map_err(|err| format!("Could not write {}: {}", path, err))?;
+    println!("{}", game_review.summary());
+    println!("Wrote the annotated game to {}", path);
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+fn setup_saving(options: &Options, game: &mut Game) -> Result<(), String> {
+    use checkers::SavedGame;
+    if let Some(path) = options.values.get("resume") {
+        let bytes = fs::read(path).map_err(|err| format!("Could not read {}: {}", path, err))?;
+        let saved = std::str::from_utf8(&bytes).ok()
+            .and_then(|text| SavedGame::from_json(text).ok())
+            .or_else(|| SavedGame::from_bytes(&bytes).ok())
+            .ok_or(format!("{} is not a saved game.", path))?;
+        game.load(saved).map_err(|err| format!("Could not resume {}: {}.", path, err))?;
+    }
+    game.autosave = options.values.get("save").cloned();
+    Ok(())
+}
+
+#[cfg(not(feature = "serde"))]
+fn setup_saving(options: &Options, _game: &mut Game) -> Result<(), String> {
+    if options.values.contains_key("save") || options.values.contains_key("resume") {
+        return Err("Saving games requires building with --features serde.".to_string());
+    }
+    Ok(())
+}
+
+fn analyse(options: &Options) -> Result<(), String> {
+    let fen = match options.positional.as_slice() {
+        [fen] => fen,
+        [] => return Err("Missing position. Usage: checkers analyse <FEN>".to_string()),
+        [_, arg, ..] => return Err(format!("Unexpected argument '{}'.", arg)),
+    };
+    let (board, color) = board_from_fen(fen).map_err(|err| format!("Invalid FEN: {}.", err))?;
+    let depth = options.get_in_range("depth", 5, 1, 12)?;
+    let estimator = estimator(options)?;
+    println!("{}", board.repr());
+    println!("{:?} to move", color);
+    let legal_moves = MoveExecutor::get_legal_moves(&board, color);
+    if legal_moves.is_empty() {
+        println!("No legal moves, {:?} wins.", color.opposite_color());
+        return Ok(());
+    }
+    let analysis = match options.values.get("time") {
+        Some(_) => {
+            let time_limit = Duration::from_millis(options.get("time", 0)?);
+            analysis::analyse_timed(board, color, depth, time_limit, estimator.as_ref())
+        }
+        None => MinMaxBot::new("MinMax", color, depth, estimator.as_ref()).analyse(board, color),
+    };
+    println!("{:<24} {:>8}  line", "move", "score");
+    for move_score in &analysis.moves {
+        let line: Vec<String> = move_score.line.iter().map(|m| m.to_string()).collect();
+        println!("{:<24} {:>8}  {}", move_score.game_move.to_string(), format_score(move_score.score), line.join(" "));
+    }
+    let pv: Vec<String> = analysis.principal_variation.iter().map(|m| m.to_string()).collect();
+    println!("\nBest line at depth {}: {}", analysis.depth, pv.join(" "));
+    println!("Score: {}", analysis.score().map(format_score).unwrap_or_default());
+    println!("{}", analysis.stats.report());
+    if let Some(path) = options.values.get("stats") {
+        write_stats(&analysis.stats, path)?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+fn write_stats(stats: &SearchStats, path: &str) -> Result<(), String> {
+    fs::write(path, stats.to_json()).map_err(|err| format!("Could not write {}: {}", path, err))
+}
+
+#[cfg(not(feature = "serde"))]
+fn write_stats(_stats: &SearchStats, _path: &str) -> Result<(), String> {
+    Err("Writing statistics requires building with --features serde.".to_string())
+}
+
+fn perft(options: &Options) -> Result<(), String> {
+    let (board, color) = match options.values.get("fen") {
+        Some(fen) => board_from_fen(fen).map_err(|err| format!("Invalid FEN: {}.", err))?,
+        None => (Board::new(options.get_in_range("rows", 3, 1, 3)?), CheckersColor::White),
+    };
+    let depth = options.get_in_range("depth", 5, 1, 12)?;
+    for current_depth in 1..=depth {
+        let start = Instant::now();
+        let nodes = MoveExecutor::perft(&board, color, current_depth);
+        println!("depth {:>2}: {:>12} nodes in {:?}", current_depth, nodes, start.elapsed());
+    }
+    Ok(())
+}
+
+fn play_match(options: &Options) -> Result<(), String> {
+    let games = options.get_in_range("games", 10, 1, 10000)?;
+    let rows = options.get_in_range("rows", 3, 1, 3)?;
+    let estimator = estimator(options)?;
+    if bot_count(options, "minmax", "minmax") < 2 {
+        return Err("Both players of a match have to be bots.".to_string());
+    }
+    let mut white_wins = 0;
+    let mut black_wins = 0;
+    let mut draws = 0;
+    let log_dir = options.values.get("log-dir");
+    if let Some(dir) = log_dir {
+        fs::create_dir_all(dir).map_err(|err| format!("Could not create {}: {}", dir, err))?;
+    }
+    for i in 1..=games {
+        let mut white = create_player(options, CheckersColor::White, "minmax", estimator.as_ref())?;
+        let mut black = create_player(options, CheckersColor::Black, "minmax", estimator.as_ref())?;
+        let mut game = Game::new_with_bots(white.as_mut(), black.as_mut(), 2, rows);
+        game.allow_first_random = true;
+        game.verbose = false;
+        game.log_file = log_dir.map(|dir| format!("{}/game-{}.log", dir, i));
+        let result = game.play();
+        match result {
+            GameResult::Win(CheckersColor::White) => white_wins += 1,
+            GameResult::Win(CheckersColor::Black) => black_wins += 1,
+            GameResult::Draw => draws += 1,
+        }
+        println!("Game {}: {} after {} moves", i, result, game.moves().len());
+    }
+    println!("White wins: {}, Black wins: {}, Draws: {}", white_wins, black_wins, draws);
+    Ok(())
+}
+
+fn train(options: &Options) -> Result<(), String> {
+    if let Some(arg) = options.positional.first() {
+        return Err(format!("Unexpected argument '{}'.", arg));
+    }
+    let games = options.get_in_range("games", 50, 1, 100000)?;
+    let depth = options.get_in_range("depth", 3, 1, 8)?;
+    let random_plies = options.get_in_range("random-plies", 6, 0, 40)?;
+    let epochs = options.get_in_range("epochs", 20, 1, 10000)?;
+    let learning_rate: f32 = options.get("learning-rate", 0.01)?;
+    let output = options.text("output", "neural.txt");
+    let mut rng = StdRng::seed_from_u64(options.get("seed", 0)?);
+    let mut network = match options.values.get("weights") {
+        Some(path) => NeuralEstimator::load(path).map_err(|err| format!("Could not load {}: {}.", path, err))?,
+        None => {
+            NeuralEstimator::new(&hidden_sizes(options, "32")?, &mut rng)
+        }
+    };
+    let start = Instant::now();
+    let samples = neural::self_play(games, depth, random_plies, &CountEstimator::new(1, 3), &mut rng);
+    println!("{} positions from {} games in {:?}", samples.len(), games, start.elapsed());
+    for epoch in 1..=epochs {
+        let loss = network.train_epoch(&samples, learning_rate, &mut rng);
+        println!("epoch {:>4}: loss {:.4}", epoch, loss);
+    }
+    network.save(&output).map_err(|err| format!("Could not write {}: {}.", output, err))?;
+    println!("Saved the network to {}", output);
+    Ok(())
+}
+
+fn export(options: &Options) -> Result<(), String> {
+    let games = options.get_in_range("games", 10, 1, 100000)?;
+    let rows = options.get_in_range("rows", 3, 1, 3)?;
+    let config = ExportConfig {
+        depth: options.get_in_range("label-depth", 4, 1, 12)?,
+        skip_plies: options.get_in_range("skip-plies", 4, 0, 1000)?,
+        sample_rate: options.get("sample-rate", 1.0)?,
+    };
+    if !(0.0..=1.0).contains(&config.sample_rate) {
+        return Err("Invalid value for --sample-rate. Should be between 0 and 1.".to_string());
+    }
+    let output = options.text("output", "positions.csv");
+    let estimator = estimator(options)?;
+    if bot_count(options, "minmax", "minmax") < 2 {
+        return Err("Both players of an export have to be bots.".to_string());
+    }
+    let mut rng = StdRng::seed_from_u64(options.get("seed", 0)?);
+    let recorder = PositionRecorder::new();
+    let mut records = Vec::new();
+    for i in 1..=games {
+        let mut white = create_player(options, CheckersColor::White, "minmax", estimator.as_ref())?;
+        let mut black = create_player(options, CheckersColor::Black, "minmax", estimator.as_ref())?;
+        let mut game = Game::new_with_bots(white.as_mut(), black.as_mut(), 2, rows);
+        game.allow_first_random = true;
+        game.verbose = false;
+        game.add_observer(&recorder);
+        let result = game.play();
+        let labelled = training_data::label_positions(&recorder.take(), result, &config, estimator.as_ref(), &mut rng);
+        println!("Game {}: {} after {} moves, {} positions", i, result, game.moves().len(), labelled.len());
+        records.extend(labelled);
+    }
+    let mut file = fs::File::create(&output).map_err(|err| format!("Could not write {}: {}.", output, err))?;
+    training_data::write_csv(&mut file, &records).map_err(|err| format!("Could not write {}: {}.", output, err))?;
+    println!("Wrote {} positions to {}", records.len(), output);
+    Ok(())
+}
+
+fn find_puzzles(options: &Options) -> Result<(), String> {
+    let config = PuzzleConfig {
+        depth: options.get_in_range("puzzle-depth", 5, 2, 8)?,
+        min_gain: options.get("min-gain", 1)?,
+    };
+    let output = options.text("output", "puzzles.pdn");
+    let mut positions = Vec::new();
+    match options.positional.as_slice() {
+        [path] => {
+            let text = fs::read_to_string(path).map_err(|err| format!("Could not read {}: {}", path, err))?;
+            let games = PdnGame::parse_all(&text).map_err(|err| format!("Could not parse {}: {}.", path, err))?;
+            for (i, pdn) in games.iter().enumerate() {
+                let (mut board, mut color) = pdn.start_position().map_err(|err| format!("Game {}: invalid FEN tag: {}.", i + 1, err))?;
+                let moves = pdn.game_moves().map_err(|err| format!("Game {}: could not replay the game: {}.", i + 1, err))?;
+                for game_move in &moves {
+                    positions.push((board, color));
+                    board = MoveExecutor::promote_to_queen(&MoveExecutor::execute(&board, game_move));
+                    color = color.opposite_color();
+                }
+            }
+            println!("{} positions from {} games in {}", positions.len(), games.len(), path);
+        }
+        [] => {
+            let games = options.get_in_range("games", 10, 1, 100000)?;
+            let rows = options.get_in_range("rows", 3, 1, 3)?;
+            let estimator = estimator(options)?;
+            if bot_count(options, "minmax", "minmax") < 2 {
+                return Err("Both players have to be bots.".to_string());
+            }
+            let recorder = PositionRecorder::new();
+            for _ in 0..games {
+                let mut white = create_player(options, CheckersColor::White, "minmax", estimator.as_ref())?;
+                let mut black = create_player(options, CheckersColor::Black, "minmax", estimator.as_ref())?;
+                let mut game = Game::new_with_bots(white.as_mut(), black.as_mut(), 2, rows);
+                game.allow_first_random = true;
+                game.verbose = false;
+                game.add_observer(&recorder);
+                game.play();
+                positions.extend(recorder.take());
+            }
+            println!("{} positions from {} bot games", positions.len(), games);
+        }
+        [_, arg, ..] => return Err(format!("Unexpected argument '{}'.", arg)),
+    }
+    let found = puzzles::find_puzzles(&positions, &config);
+    let text: String = found.iter().map(|puzzle| puzzle.to_pdn_game().to_pdn() + "\n").collect();
+    fs::write(&output, text).map_err(|err| format!("Could not write {}: {}.", output, err))?;
+    println!("Wrote {} puzzles to {}", found.len(), output);
+    Ok(())
+}
+
+fn solve(options: &Options) -> Result<(), String> {
+    let path = match options.positional.as_slice() {
+        [path] => path,
+        [] => return Err("Missing file. Usage: checkers solve <file.pdn>".to_string()),
+        [_, arg, ..] => return Err(format!("Unexpected argument '{}'.", arg)),
+    };
+    let text = fs::read_to_string(path).map_err(|err| format!("Could not read {}: {}", path, err))?;
+    let games = PdnGame::parse_all(&text).map_err(|err| format!("Could not parse {}: {}.", path, err))?;
+    let puzzles = games.iter().enumerate()
+        .map(|(i, game)| Puzzle::from_pdn_game(game).map_err(|err| format!("Puzzle {}: {}.", i + 1, err)))
+        .collect::<Result<Vec<Puzzle>, String>>()?;
+    let depth = options.get_in_range("depth", 6, 1, 10)?;
+    let start = options.get_in_range("start", 1, 1, puzzles.len().max(1))?;
+    let scores_path = options.text("scores", "puzzle-scores.txt");
+    let mut scores = PuzzleScores::load(&scores_path).map_err(|err| format!("Could not load {}: {}.", scores_path, err))?;
+    println!("Type hint for a hint, undo to start the puzzle again and resign to see the solution.");
+    for (i, puzzle) in puzzles.iter().enumerate().skip(start - 1) {
+        let fen = fen_from_board(&puzzle.board, puzzle.side_to_move);
+        if options.flag("unsolved") && scores.get(&fen).is_some_and(|score| score.solved > 0) {
+            continue;
+        }
+        let goal = match puzzle.gain {
+            i32::MAX => "win the game".to_string(),
+            1 => "win a pawn".to_string(),
+            gain => format!("win {} pawns", gain),
+        };
+        println!("\nPuzzle {}/{}: {:?} to move and {}.", i + 1, puzzles.len(), puzzle.side_to_move, goal);
+        let mut human = Human::new(&options.text("name", "Human"), puzzle.side_to_move);
+        let mut session = PuzzleSession::new(puzzle, depth);
+        let mut hints = 0;
+        let solved = loop {
+            println!("{}", session.board().repr());
+            human.set_hints(session.hints());
+            let legal_moves = session.legal_moves();
+            let decision = players::decide(&human, session.board(), &legal_moves, false);
+            hints += human.hints_shown();
+            match decision {
+                Decision::Move(index) => match session.play(&legal_moves[index]) {
+                    PuzzleStep::Solved => break true,
+                    PuzzleStep::Wrong => println!("That is not the winning move, try again."),
+                    PuzzleStep::Correct(reply) => println!("Correct. The opponent answers {}.", notation::move_to_pdn(&reply)),
+                },
+                Decision::Undo => session.restart(),
+                Decision::Resign => break false,
+                Decision::OfferDraw => println!("There are no draws in puzzles."),
+            }
+        };
+        let points = if solved { Some(puzzles::puzzle_points(hints, session.mistakes())) } else { None };
+        scores.record(&fen, points);
+        scores.save(&scores_path).map_err(|err| format!("Could not write {}: {}.", scores_path, err))?;
+        match points {
+            Some(points) => println!("Solved for {} point{}!", points, if points == 1 { "" } else { "s" }),
+            None => {
+                let solution: Vec<String> = puzzle.solution.iter().map(notation::move_to_pdn).collect();
+                println!("The solution was {}.", solution.join(" "));
+            }
+        }
+        println!("Solved {} puzzles, {} points in total.", scores.solved_count(), scores.total_points());
+        if !solved && !ask_yes_no("Next puzzle? [y/n] ") {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn ask_yes_no(question: &str) -> bool {
+    use std::io::{stdin, stdout, Write};
+    print!("{}", question);
+    let _ = stdout().flush();
+    let mut line = String::new();
+    let _ = stdin().read_line(&mut line);
+    matches!(line.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+fn hidden_sizes(options: &Options, default: &str) -> Result<Vec<usize>, String> {
+    options.text("hidden", default).split(',')
+        .map(|size| size.trim().parse::<usize>().ok().filter(|&size| size > 0))
+        .collect::<Option<Vec<usize>>>()
+        .ok_or_else(|| "Invalid value for --hidden. Expected sizes like 32 or 64,16.".to_string())
+}
+
+fn train_mcts(options: &Options) -> Result<(), String> {
+    if let Some(arg) = options.positional.first() {
+        return Err(format!("Unexpected argument '{}'.", arg));
+    }
+    let config = SelfPlayConfig {
+        pawn_rows: options.get_in_range("rows", 2, 1, 3)?,
+        simulations: options.get_in_range("simulations", 100, 1, 1000000)?,
+        ..SelfPlayConfig::default()
+    };
+    let iterations = options.get_in_range("iterations", 10, 1, 100000)?;
+    let games = options.get_in_range("games", 20, 1, 100000)?;
+    let steps = options.get_in_range("steps", 100, 1, 1000000)?;
+    let batch = options.get_in_range("batch", 32, 1, 100000)?;
+    let capacity = options.get_in_range("capacity", 20000, 1, 10000000)?;
+    let learning_rate: f32 = options.get("learning-rate", 0.01)?;
+    let output = options.text("output", "policy.txt");
+    let mut rng = StdRng::seed_from_u64(options.get("seed", 0)?);
+    let mut net = match options.values.get("net") {
+        Some(path) => PolicyValueNet::load(path).map_err(|err| format!("Could not load {}: {}.", path, err))?,
+        None => PolicyValueNet::new(&hidden_sizes(options, "64")?, &mut rng),
+    };
+    let buffer_path = options.values.get("buffer");
+    let mut buffer = match buffer_path {
+        Some(path) if fs::metadata(path).is_ok() => ReplayBuffer::load(path, capacity)
+            .map_err(|err| format!("Could not load {}: {}.", path, err))?,
+        _ => ReplayBuffer::new(capacity),
+    };
+    for iteration in 1..=iterations {
+        let start = Instant::now();
+        for _ in 0..games {
+            buffer.extend(self_play::self_play_game(&net, &config, &mut rng));
+        }
+        let (value_loss, policy_loss) = self_play::train(&mut net, &buffer, steps, batch, learning_rate, &mut rng);
+        println!("iteration {:>4}: {} positions, value loss {:.4}, policy loss {:.4} in {:?}",
+            iteration, buffer.len(), value_loss, policy_loss, start.elapsed());
+        net.save(&output).map_err(|err| format!("Could not write {}: {}.", output, err))?;
+        if let Some(path) = buffer_path {
+            buffer.save(path).map_err(|err| format!("Could not write {}: {}.", path, err))?;
+        }
+    }
+    println!("Saved the network to {}", output);
+    Ok(())
+}
+
+fn review_pdn(options: &Options) -> Result<(), String> {
+    let path = match options.positional.as_slice() {
+        [path] => path,
+        [] => return Err("Missing file. Usage: checkers review <file.pdn>".to_string()),
+        [_, arg, ..] => return Err(format!("Unexpected argument '{}'.", arg)),
+    };
+    let text = fs::read_to_string(path).map_err(|err| format!("Could not read {}: {}", path, err))?;
+    let games = PdnGame::parse_all(&text).map_err(|err| format!("Could not parse {}: {}.", path, err))?;
+    let number = options.get_in_range("game", 1, 1, games.len().max(1))?;
+    let pdn = games.get(number - 1).ok_or(format!("No games found in {}.", path))?;
+    let (board, color) = pdn.start_position().map_err(|err| format!("Invalid FEN tag: {}.", err))?;
+    let moves = pdn.game_moves().map_err(|err| format!("Could not replay the game: {}.", err))?;
+    let estimator = estimator(options)?;
+    write_review(options, board, color, &moves, &pdn.tags, &pdn.result, &options.text("output", "review.pdn"), estimator.as_ref())
+}
+
+fn replay(options: &Options) -> Result<(), String> {
+    let path = match options.positional.as_slice() {
+        [path] => path,
+        [] => return Err("Missing file. Usage: checkers replay <file.pdn>".to_string()),
+        [_, arg, ..] => return Err(format!("Unexpected argument '{}'.", arg)),
+    };
+    let text = fs::read_to_string(path).map_err(|err| format!("Could not read {}: {}", path, err))?;
+    let games = PdnGame::parse_all(&text).map_err(|err| format!("Could not parse {}: {}.", path, err))?;
+    let number = options.get_in_range("game", 1, 1, games.len().max(1))?;
+    let pdn = games.get(number - 1).ok_or(format!("No games found in {}.", path))?;
+    let mut replay = Replay::from_pdn(pdn).map_err(|err| format!("Could not replay the game: {}.", err))?;
+    replay.flipped = options.flag("flip");
+    for (name, value) in &pdn.tags {
+        println!("{}: {}", name, value);
+    }
+    if options.flag("all") {
+        println!("{}", replay.render());
+        while replay.forward() {
+            println!("{}", replay.render());
+        }
+        println!("Result: {}", pdn.result);
+        return Ok(());
+    }
+    view_replay(&mut replay);
+    println!("Result: {}", pdn.result);
+    Ok(())
+}
+
+const REPLAY_HELP: &str = "Enter or n: next move, b: previous move, <ply>: jump to a ply, s: start, e: end, \
+f: flip the board, q: quit";
+
+fn view_replay(replay: &mut Replay) {
+    use std::io::{stdin, stdout, Write};
+    println!("{}", REPLAY_HELP);
+    println!("{}", replay.render());
+    loop {
+        print!("> ");
+        let _ = stdout().flush();
+        let mut line = String::new();
+        if stdin().read_line(&mut line).unwrap_or(0) == 0 {
+            return;
+        }
+        let moved = match line.trim() {
+            "" | "n" => replay.forward(),
+            "b" => replay.back(),
+            "s" => {
+                replay.first();
+                true
+            }
+            "e" => {
+                replay.last();
+                true
+            }
+            "f" => {
+                replay.flip();
+                true
+            }
+            "q" => return,
+            other => match other.parse::<usize>() {
+                Ok(ply) if replay.jump(ply) => true,
+                Ok(_) => {
+                    println!("The game has {} plies.", replay.len());
+                    continue;
+                }
+                Err(_) => {
+                    println!("{}", REPLAY_HELP);
+                    continue;
+                }
+            },
+        };
+        if moved {
+            println!("{}", replay.render());
+        } else {
+            println!("No more moves in this direction.");
+        }
+    }
+}
+
+fn diagram(options: &Options) -> Result<(), String> {
+    let (board, color, last_move) = match (options.values.get("pdn"), options.positional.as_slice()) {
+        (Some(path), []) => pdn_position(options, path)?,
+        (None, [fen]) => {
+            let (board, color) = board_from_fen(fen).map_err(|err| format!("Invalid FEN: {}.", err))?;
+            (board, color, None)
+        }
+        (None, []) => (Board::new(3), CheckersColor::White, None),
+        (_, [arg, ..]) => return Err(format!("Unexpected argument '{}'.", arg)),
+    };
+    let mut diagram = Diagram::new(board);
+    diagram.flipped = options.flag("flip");
+    diagram.coordinates = !options.flag("no-coordinates");
+    diagram.square_size = options.get_in_range("size", 60, 10, 500)?;
+    if let Some(game_move) = last_move {
+        diagram.add_arrow(&game_move);
+    }
+    if let Some(text) = options.values.get("move") {
+        let squares = text.split(['-', 'x', 'X', ':'])
+            .map(notation::parse_square)
+            .collect::<Result<Vec<(usize, usize)>, _>>()
+            .map_err(|err| format!("Invalid move '{}': {}.", text, err))?;
+        let moves = MoveExecutor::get_legal_moves(&board, color);
+        match notation::moves_matching_path(&moves, &squares).as_slice() {
+            [index] => diagram.add_arrow(&moves[*index]),
+            [] => return Err(format!("'{}' is not a legal move for {:?}.", text, color)),
+            _ => return Err(format!("'{}' is ambiguous, give the full capture path.", text)),
+        }
+    }
+    for square in options.text("highlight", "").split(',').filter(|s| !s.trim().is_empty()) {
+        let (x, y) = notation::parse_square(square).map_err(|err| format!("Invalid square: {}.", err))?;
+        diagram.highlight(x, y);
+    }
+    match options.values.get("output") {
+        Some(path) if path.to_lowercase().ends_with(".png") => write_png(&diagram, path),
+        Some(path) => fs::write(path, diagram.to_svg()).map_err(|err| format!("Could not write {}: {}", path, err)),
+        None => {
+            print!("{}", diagram.to_svg());
+            Ok(())
+        }
+    }
+}
+
+fn pdn_position(options: &Options, path: &str) -> Result<(Board, CheckersColor, Option<GameMove>), String> {
+    let text = fs::read_to_string(path).map_err(|err| format!("Could not read {}: {}", path, err))?;
+    let games = PdnGame::parse_all(&text).map_err(|err| format!("Could not parse {}: {}.", path, err))?;
+    let number = options.get_in_range("game", 1, 1, games.len().max(1))?;
+    let pdn = games.get(number - 1).ok_or(format!("No games found in {}.", path))?;
+    let (mut board, mut color) = pdn.start_position().map_err(|err| format!("Invalid FEN tag: {}.", err))?;
+    let moves = pdn.game_moves().map_err(|err| format!("Could not replay the game: {}.", err))?;
+    let ply = options.get_in_range("ply", moves.len(), 0, moves.len())?;
+    for game_move in &moves[..ply] {
+        board = MoveExecutor::promote_to_queen(&MoveExecutor::execute(&board, game_move));
+        color = color.opposite_color();
+    }
+    let last_move = if ply > 0 { Some(moves[ply - 1].clone()) } else { None };
+    Ok((board, color, last_move))
+}
+
+#[cfg(feature = "png")]
+fn write_png(diagram: &Diagram, path: &str) -> Result<(), String> {
+    let png = diagram.to_png().map_err(|err| format!("Could not render the diagram: {}", err))?;
+    fs::write(path, png).map_err(|err| format!("Could not write {}: {}", path, err))
+}
+
+#[cfg(not(feature = "png"))]
+fn write_png(_diagram: &Diagram, _path: &str) -> Result<(), String> {
+    Err("PNG output requires building with --features png.".to_string())
+}
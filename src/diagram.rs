@@ -0,0 +1,157 @@
+use std::fmt::Write;
+use crate::{Board, CheckersColor, Piece};
+use crate::moves::GameMove;
+
+const LIGHT_SQUARE: &str = "#f0d9b5";
+const DARK_SQUARE: &str = "#b58863";
+const HIGHLIGHT: &str = "#f7ec5a";
+const ARROW: &str = "#2f7dd1";
+const WHITE_PIECE: &str = "#f8f8f8";
+const BLACK_PIECE: &str = "#262626";
+const CROWN: &str = "#d4a017";
+const FONT: &str = "DejaVu Sans, Arial, Helvetica, sans-serif";
+
+pub struct Diagram {
+    pub board: Board,
+    pub flipped: bool,
+    pub coordinates: bool,
+    pub square_size: usize,
+    pub highlighted: Vec<(usize, usize)>,
+    pub arrows: Vec<Vec<(usize, usize)>>,
+}
+
+impl Diagram {
+    pub fn new(board: Board) -> Self {
+        Self {
+            board,
+            flipped: false,
+            coordinates: true,
+            square_size: 60,
+            highlighted: Vec::new(),
+            arrows: Vec::new(),
+        }
+    }
+
+    pub fn for_color(board: Board, color: CheckersColor) -> Self {
+        let mut diagram = Self::new(board);
+        diagram.flipped = color == CheckersColor::Black;
+        diagram
+    }
+
+    pub fn add_arrow(&mut self, game_move: &GameMove) {
+        self.arrows.push(game_move.path());
+    }
+
+    pub fn highlight(&mut self, x: usize, y: usize) {
+        self.highlighted.push((x, y));
+    }
+
+    pub fn size(&self) -> usize {
+        8 * self.square_size + 2 * self.margin()
+    }
+
+    fn margin(&self) -> usize {
+        if self.coordinates { self.square_size / 2 } else { 0 }
+    }
+
+    // Top left corner of the square (x, y) in the picture, x being the board row.
+    fn position(&self, x: usize, y: usize) -> (usize, usize) {
+        let (row, column) = if self.flipped { (7 - x, 7 - y) } else { (x, y) };
+        (self.margin() + column * self.square_size, self.margin() + row * self.square_size)
+    }
+
+    fn center(&self, x: usize, y: usize) -> (usize, usize) {
+        let (left, top) = self.position(x, y);
+        (left + self.square_size / 2, top + self.square_size / 2)
+    }
+
+    pub fn to_svg(&self) -> String {
+        let size = self.size();
+        let s = self.square_size;
+        let mut svg = String::new();
+        let _ = writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{0}" viewBox="0 0 {0} {0}">"#, size);
+        let _ = writeln!(svg, r#"<defs><marker id="arrowhead" markerWidth="4" markerHeight="4" refX="2.5" refY="2" orient="auto"><path d="M0,0 L4,2 L0,4 z" fill="{}"/></marker></defs>"#, ARROW);
+        let _ = writeln!(svg, r#"<rect width="{0}" height="{0}" fill="white"/>"#, size);
+        for x in 0..8 {
+            for y in 0..8 {
+                let (left, top) = self.position(x, y);
+                let fill = if (x + y) % 2 == 0 { LIGHT_SQUARE } else { DARK_SQUARE };
+                let _ = writeln!(svg, r#"<rect class="square" x="{}" y="{}" width="{s}" height="{s}" fill="{}"/>"#, left, top, fill, s = s);
+            }
+        }
+        for &(x, y) in &self.highlighted {
+            let (left, top) = self.position(x, y);
+            let _ = writeln!(svg, r#"<rect class="highlight" x="{}" y="{}" width="{s}" height="{s}" fill="{}" fill-opacity="0.6"/>"#, left, top, HIGHLIGHT, s = s);
+        }
+        for x in 0..8 {
+            for y in 0..8 {
+                if let Ok(Some(piece)) = self.board.get_at(x, y) {
+                    self.write_piece(&mut svg, x, y, piece);
+                }
+            }
+        }
+        for path in self.arrows.iter().filter(|path| path.len() > 1) {
+            let points: Vec<String> = path.iter()
+                .map(|&(x, y)| {
+                    let (cx, cy) = self.center(x, y);
+                    format!("{},{}", cx, cy)
+                })
+                .collect();
+            let _ = writeln!(svg, r#"<polyline class="arrow" points="{}" fill="none" stroke="{}" stroke-width="{}" stroke-opacity="0.8" stroke-linejoin="round" marker-end="url(#arrowhead)"/>"#,
+                points.join(" "), ARROW, s / 8);
+        }
+        if self.coordinates {
+            self.write_coordinates(&mut svg);
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    fn write_piece(&self, svg: &mut String, x: usize, y: usize, piece: Piece) {
+        let (cx, cy) = self.center(x, y);
+        let radius = self.square_size * 2 / 5;
+        let (class, fill, stroke) = match piece {
+            Piece::Pawn(CheckersColor::White) => ("white-pawn", WHITE_PIECE, BLACK_PIECE),
+            Piece::Queen(CheckersColor::White) => ("white-queen", WHITE_PIECE, BLACK_PIECE),
+            Piece::Pawn(CheckersColor::Black) => ("black-pawn", BLACK_PIECE, WHITE_PIECE),
+            Piece::Queen(CheckersColor::Black) => ("black-queen", BLACK_PIECE, WHITE_PIECE),
+        };
+        let _ = writeln!(svg, r#"<circle class="piece {}" cx="{}" cy="{}" r="{}" fill="{}" stroke="{}" stroke-width="2"/>"#,
+            class, cx, cy, radius, fill, stroke);
+        if let Piece::Queen(_) = piece {
+            let _ = writeln!(svg, r#"<circle cx="{}" cy="{}" r="{}" fill="none" stroke="{}" stroke-width="{}"/>"#,
+                cx, cy, radius / 2, CROWN, self.square_size / 15 + 1);
+        }
+    }
+
+    fn write_coordinates(&self, svg: &mut String) {
+        let margin = self.margin();
+        let font_size = self.square_size / 3;
+        let far_side = margin + 8 * self.square_size + margin / 2;
+        for i in 0..8 {
+            let (cx, cy) = self.center(i, i);
+            let file = (b'a' + i as u8) as char;
+            let rank = 8 - i;
+            for y in [margin / 2, far_side] {
+                let _ = writeln!(svg, r#"<text x="{}" y="{}" font-family="{}" font-size="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+                    cx, y, FONT, font_size, file);
+            }
+            for x in [margin / 2, far_side] {
+                let _ = writeln!(svg, r#"<text x="{}" y="{}" font-family="{}" font-size="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+                    x, cy, FONT, font_size, rank);
+            }
+        }
+    }
+
+    #[cfg(feature = "png")]
+    pub fn to_png(&self) -> Result<Vec<u8>, String> {
+        use resvg::{tiny_skia, usvg};
+        let mut options = usvg::Options::default();
+        options.fontdb_mut().load_system_fonts();
+        let tree = usvg::Tree::from_str(&self.to_svg(), &options).map_err(|err| err.to_string())?;
+        let size = self.size() as u32;
+        let mut pixmap = tiny_skia::Pixmap::new(size, size).ok_or("Could not allocate the image.")?;
+        resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+        pixmap.encode_png().map_err(|err| err.to_string())
+    }
+}
@@ -0,0 +1,78 @@
+use std::fmt::{Display, Formatter};
+use std::io;
+use crate::checkers_utils::alias_from_coordinates;
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum CheckersError {
+    // board access
+    IndexOutOfBounds { x: usize, y: usize },
+    LightSquare { x: usize, y: usize },
+    PawnBinaryValueError(u8),
+    // notation
+    NotationError { text: String, reason: &'static str },
+    AmbiguousMove(String),
+    // rules
+    NoPiece { x: usize, y: usize },
+    WrongColor { x: usize, y: usize },
+    IllegalMove(String),
+    CaptureRequired(String),
+    InvalidConfiguration(String),
+    GameOver(String),
+    // persistence and communication
+    SerializationError(String),
+    Io { kind: io::ErrorKind, message: String },
+    Protocol(String),
+}
+
+impl CheckersError {
+    pub fn notation(text: &str, reason: &'static str) -> Self {
+        CheckersError::NotationError { text: text.to_string(), reason }
+    }
+}
+
+fn square(x: usize, y: usize) -> String {
+    alias_from_coordinates(x, y).unwrap_or_else(|_| format!("({}, {})", x, y))
+}
+
+impl Display for CheckersError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckersError::IndexOutOfBounds { x, y } => write!(f, "field ({}, {}) is outside of the board", x, y),
+            CheckersError::LightSquare { x, y } => write!(f, "{} is a light square and cannot hold a piece", square(*x, *y)),
+            CheckersError::PawnBinaryValueError(value) => write!(f, "{:#06b} is not a valid value of a board field", value),
+            CheckersError::NotationError { text, reason } => write!(f, "invalid notation '{}': {}", text, reason),
+            CheckersError::AmbiguousMove(text) => write!(f, "move '{}' is ambiguous", text),
+            CheckersError::NoPiece { x, y } => write!(f, "there is no piece on {}", square(*x, *y)),
+            CheckersError::WrongColor { x, y } => write!(f, "the piece on {} belongs to the other player", square(*x, *y)),
+            CheckersError::IllegalMove(text) => write!(f, "{} is not a legal move", text),
+            CheckersError::CaptureRequired(text) => write!(f, "{} is not a capture but capturing is mandatory", text),
+            CheckersError::InvalidConfiguration(message) => write!(f, "invalid game configuration: {}", message),
+            CheckersError::GameOver(result) => write!(f, "the game is already over: {}", result),
+            CheckersError::SerializationError(message) => write!(f, "invalid serialized data: {}", message),
+            CheckersError::Io { message, .. } => write!(f, "i/o error: {}", message),
+            CheckersError::Protocol(message) => write!(f, "protocol error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for CheckersError {}
+
+impl From<io::Error> for CheckersError {
+    fn from(err: io::Error) -> Self {
+        CheckersError::Io { kind: err.kind(), message: err.to_string() }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<serde_json::Error> for CheckersError {
+    fn from(err: serde_json::Error) -> Self {
+        CheckersError::SerializationError(err.to_string())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<bincode::Error> for CheckersError {
+    fn from(err: bincode::Error) -> Self {
+        CheckersError::SerializationError(err.to_string())
+    }
+}
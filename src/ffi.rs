@@ -0,0 +1,353 @@
+// The C library is built with: cargo rustc --lib --release --features ffi --crate-type cdylib
+use std::ffi::{c_char, CStr};
+use std::time::Duration;
+use crate::{analysis, Board, CheckersColor, CountEstimator, GameMove, GameResult, GameState, GameStatus, MoveExecutor};
+use crate::checkers_utils::CheckersError;
+use crate::notation::{board_from_fen, coordinates_from_square, fen_from_board, square_number};
+
+// Longest capture path: start square and one landing square per captured piece.
+pub const CHECKERS_MAX_PATH: usize = 16;
+
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum CheckersStatus {
+    Ok = 0,
+    NullPointer,
+    InvalidArgument,
+    IllegalMove,
+    GameOver,
+    NoMoves,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum CheckersSide {
+    White = 0,
+    Black,
+    // Returned for a NULL handle.
+    Invalid,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum CheckersOutcome {
+    InProgress = 0,
+    WhiteWins,
+    BlackWins,
+    Draw,
+    // Returned for a NULL handle.
+    Invalid,
+}
+
+// A move as the squares (1-32) it passes through, captured pieces are not listed.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct CheckersMove {
+    pub squares: [u8; CHECKERS_MAX_PATH],
+    pub length: u8,
+    pub is_capture: bool,
+}
+
+// Opaque position handle: a board and the side to move.
+pub struct CheckersBoard {
+    board: Board,
+    side_to_move: CheckersColor,
+}
+
+// Opaque game handle with move history, undo and draw rules.
+pub struct CheckersGame {
+    state: GameState,
+}
+
+impl From<&GameMove> for CheckersMove {
+    fn from(game_move: &GameMove) -> Self {
+        let path = game_move.path();
+        let mut squares = [0; CHECKERS_MAX_PATH];
+        for (square, &(x, y)) in squares.iter_mut().zip(path.iter()) {
+            *square = square_number(x, y).unwrap() as u8;
+        }
+        CheckersMove {
+            squares,
+            length: path.len().min(CHECKERS_MAX_PATH) as u8,
+            is_capture: game_move.is_capture(),
+        }
+    }
+}
+
+impl From<CheckersError> for CheckersStatus {
+    fn from(err: CheckersError) -> Self {
+        match err {
+            CheckersError::GameOver(_) => CheckersStatus::GameOver,
+            CheckersError::NoPiece { .. } | CheckersError::WrongColor { .. } | CheckersError::IllegalMove(_)
+            | CheckersError::CaptureRequired(_) | CheckersError::AmbiguousMove(_) => CheckersStatus::IllegalMove,
+            _ => CheckersStatus::InvalidArgument,
+        }
+    }
+}
+
+fn side(color: CheckersColor) -> CheckersSide {
+    match color {
+        CheckersColor::White => CheckersSide::White,
+        CheckersColor::Black => CheckersSide::Black,
+    }
+}
+
+fn find_move(board: &Board, color: CheckersColor, wanted: &CheckersMove) -> Result<GameMove, CheckersStatus> {
+    let length = wanted.length as usize;
+    if !(2..=CHECKERS_MAX_PATH).contains(&length) {
+        return Err(CheckersStatus::InvalidArgument);
+    }
+    let path = wanted.squares[..length].iter()
+        .map(|&square| coordinates_from_square(square as usize))
+        .collect::<Result<Vec<(usize, usize)>, CheckersError>>()?;
+    MoveExecutor::get_legal_moves(board, color).into_iter()
+        .find(|m| m.path() == path)
+        .ok_or(CheckersStatus::IllegalMove)
+}
+
+unsafe fn write_moves(moves: &[GameMove], out: *mut CheckersMove, capacity: usize) -> usize {
+    if !out.is_null() {
+        for (i, game_move) in moves.iter().take(capacity).enumerate() {
+            *out.add(i) = CheckersMove::from(game_move);
+        }
+    }
+    moves.len()
+}
+
+unsafe fn search(board: Board, color: CheckersColor, depth: u32, time_limit_ms: u32, best: *mut CheckersMove, score: *mut i32) -> CheckersStatus {
+    if best.is_null() {
+        return CheckersStatus::NullPointer;
+    }
+    if depth == 0 {
+        return CheckersStatus::InvalidArgument;
+    }
+    let estimator = CountEstimator::new(1, 3);
+    let result = if time_limit_ms == 0 {
+        analysis::analyse(board, color, depth as usize, &estimator)
+    } else {
+        analysis::analyse_timed(board, color, depth as usize, Duration::from_millis(time_limit_ms as u64), &estimator)
+    };
+    match result.best() {
+        Some(found) => {
+            *best = CheckersMove::from(&found.game_move);
+            if !score.is_null() {
+                *score = found.score;
+            }
+            CheckersStatus::Ok
+        }
+        None => CheckersStatus::NoMoves,
+    }
+}
+
+unsafe fn position_from_fen(fen: *const c_char) -> Option<(Board, CheckersColor)> {
+    if fen.is_null() {
+        return None;
+    }
+    board_from_fen(CStr::from_ptr(fen).to_str().ok()?).ok()
+}
+
+/// Initial position with 1-3 rows of pawns, NULL for other values. Free with checkers_board_free.
+#[no_mangle]
+pub extern "C" fn checkers_board_new(rows: u32) -> *mut CheckersBoard {
+    match Board::try_new(rows as usize) {
+        Ok(board) => Box::into_raw(Box::new(CheckersBoard { board, side_to_move: CheckersColor::White })),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Position from a FEN string such as "W:W21,22:B1,2", NULL if it cannot be parsed.
+///
+/// # Safety
+/// `fen` must be NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn checkers_board_from_fen(fen: *const c_char) -> *mut CheckersBoard {
+    match position_from_fen(fen) {
+        Some((board, side_to_move)) => Box::into_raw(Box::new(CheckersBoard { board, side_to_move })),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// # Safety
+/// `board` must be NULL or a handle returned by this library that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn checkers_board_free(board: *mut CheckersBoard) {
+    if !board.is_null() {
+        drop(Box::from_raw(board));
+    }
+}
+
+/// Writes the FEN of the position as a NUL-terminated string into `buffer` when it fits
+/// and returns its length without the terminator, so a call with a NULL buffer gives the size to allocate.
+///
+/// # Safety
+/// `board` must be a valid handle, `buffer` NULL or writable for `capacity` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn checkers_board_fen(board: *const CheckersBoard, buffer: *mut c_char, capacity: usize) -> usize {
+    let Some(board) = board.as_ref() else { return 0 };
+    let fen = fen_from_board(&board.board, board.side_to_move);
+    if !buffer.is_null() && fen.len() < capacity {
+        std::ptr::copy_nonoverlapping(fen.as_ptr() as *const c_char, buffer, fen.len());
+        *buffer.add(fen.len()) = 0;
+    }
+    fen.len()
+}
+
+/// Returns `CHECKERS_SIDE_INVALID` for a NULL `board`.
+///
+/// # Safety
+/// `board` must be NULL or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn checkers_board_side_to_move(board: *const CheckersBoard) -> CheckersSide {
+    let Some(board) = board.as_ref() else { return CheckersSide::Invalid };
+    side(board.side_to_move)
+}
+
+/// Writes up to `capacity` legal moves to `out` and returns the number of legal moves.
+///
+/// # Safety
+/// `board` must be a valid handle, `out` NULL or writable for `capacity` moves.
+#[no_mangle]
+pub unsafe extern "C" fn checkers_board_legal_moves(board: *const CheckersBoard, out: *mut CheckersMove, capacity: usize) -> usize {
+    let Some(board) = board.as_ref() else { return 0 };
+    write_moves(&MoveExecutor::get_legal_moves(&board.board, board.side_to_move), out, capacity)
+}
+
+/// Plays a legal move given with its full path, the side to move changes.
+///
+/// # Safety
+/// `board` must be a valid handle, `game_move` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn checkers_board_apply(board: *mut CheckersBoard, game_move: *const CheckersMove) -> CheckersStatus {
+    let (Some(board), Some(game_move)) = (board.as_mut(), game_move.as_ref()) else { return CheckersStatus::NullPointer };
+    match find_move(&board.board, board.side_to_move, game_move) {
+        Ok(found) => {
+            board.board = MoveExecutor::promote_to_queen(&MoveExecutor::execute(&board.board, &found));
+            board.side_to_move = board.side_to_move.opposite_color();
+            CheckersStatus::Ok
+        }
+        Err(status) => status,
+    }
+}
+
+/// A NULL `board` has no moves to play and counts as over.
+///
+/// # Safety
+/// `board` must be NULL or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn checkers_board_is_game_over(board: *const CheckersBoard) -> bool {
+    let Some(board) = board.as_ref() else { return true };
+    MoveExecutor::has_game_ended(board.board, board.side_to_move)
+}
+
+/// Searches `depth` plies deep; with a non-zero `time_limit_ms` the search deepens iteratively
+/// up to `depth` and stops at the time limit. `score` may be NULL.
+///
+/// # Safety
+/// `board` must be a valid handle, `best` a writable pointer.
+#[no_mangle]
+pub unsafe extern "C" fn checkers_board_search(board: *const CheckersBoard, depth: u32, time_limit_ms: u32, best: *mut CheckersMove, score: *mut i32) -> CheckersStatus {
+    let Some(board) = board.as_ref() else { return CheckersStatus::NullPointer };
+    search(board.board, board.side_to_move, depth, time_limit_ms, best, score)
+}
+
+/// New game from the initial position with 1-3 rows of pawns, NULL for other values. Free with checkers_game_free.
+#[no_mangle]
+pub extern "C" fn checkers_game_new(rows: u32) -> *mut CheckersGame {
+    match Board::try_new(rows as usize) {
+        Ok(board) => Box::into_raw(Box::new(CheckersGame { state: GameState::from_position(board, CheckersColor::White) })),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// # Safety
+/// `fen` must be NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn checkers_game_from_fen(fen: *const c_char) -> *mut CheckersGame {
+    match position_from_fen(fen) {
+        Some((board, side_to_move)) => Box::into_raw(Box::new(CheckersGame { state: GameState::from_position(board, side_to_move) })),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// # Safety
+/// `game` must be NULL or a handle returned by this library that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn checkers_game_free(game: *mut CheckersGame) {
+    if !game.is_null() {
+        drop(Box::from_raw(game));
+    }
+}
+
+/// Current position of the game as a new board handle, free it with checkers_board_free.
+///
+/// # Safety
+/// `game` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn checkers_game_board(game: *const CheckersGame) -> *mut CheckersBoard {
+    let Some(game) = game.as_ref() else { return std::ptr::null_mut() };
+    Box::into_raw(Box::new(CheckersBoard { board: game.state.board(), side_to_move: game.state.side_to_move() }))
+}
+
+/// # Safety
+/// `game` must be a valid handle, `out` NULL or writable for `capacity` moves.
+#[no_mangle]
+pub unsafe extern "C" fn checkers_game_legal_moves(game: *const CheckersGame, out: *mut CheckersMove, capacity: usize) -> usize {
+    let Some(game) = game.as_ref() else { return 0 };
+    write_moves(&game.state.legal_moves(), out, capacity)
+}
+
+/// # Safety
+/// `game` must be a valid handle, `game_move` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn checkers_game_apply(game: *mut CheckersGame, game_move: *const CheckersMove) -> CheckersStatus {
+    let (Some(game), Some(game_move)) = (game.as_mut(), game_move.as_ref()) else { return CheckersStatus::NullPointer };
+    if game.state.status() != GameStatus::InProgress {
+        return CheckersStatus::GameOver;
+    }
+    match find_move(&game.state.board(), game.state.side_to_move(), game_move) {
+        Ok(found) => match game.state.apply(&found) {
+            Ok(()) => CheckersStatus::Ok,
+            Err(err) => err.into(),
+        },
+        Err(status) => status,
+    }
+}
+
+/// Takes back the last move, NoMoves when there is nothing to undo.
+///
+/// # Safety
+/// `game` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn checkers_game_undo(game: *mut CheckersGame) -> CheckersStatus {
+    let Some(game) = game.as_mut() else { return CheckersStatus::NullPointer };
+    match game.state.undo() {
+        Some(_) => CheckersStatus::Ok,
+        None => CheckersStatus::NoMoves,
+    }
+}
+
+/// Returns `CHECKERS_OUTCOME_INVALID` for a NULL `game`.
+///
+/// # Safety
+/// `game` must be NULL or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn checkers_game_outcome(game: *const CheckersGame) -> CheckersOutcome {
+    let Some(game) = game.as_ref() else { return CheckersOutcome::Invalid };
+    match game.state.status() {
+        GameStatus::InProgress => CheckersOutcome::InProgress,
+        GameStatus::Finished(GameResult::Win(CheckersColor::White)) => CheckersOutcome::WhiteWins,
+        GameStatus::Finished(GameResult::Win(CheckersColor::Black)) => CheckersOutcome::BlackWins,
+        GameStatus::Finished(GameResult::Draw) => CheckersOutcome::Draw,
+    }
+}
+
+/// # Safety
+/// `game` must be a valid handle, `best` a writable pointer.
+#[no_mangle]
+pub unsafe extern "C" fn checkers_game_search(game: *const CheckersGame, depth: u32, time_limit_ms: u32, best: *mut CheckersMove, score: *mut i32) -> CheckersStatus {
+    let Some(game) = game.as_ref() else { return CheckersStatus::NullPointer };
+    if game.state.status() != GameStatus::InProgress {
+        return CheckersStatus::GameOver;
+    }
+    search(game.state.board(), game.state.side_to_move(), depth, time_limit_ms, best, score)
+}
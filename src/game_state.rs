@@ -0,0 +1,139 @@
+use crate::{Board, CheckersColor, GameMove, MoveExecutor, Piece};
+use crate::checkers_game::GameResult;
+use crate::checkers_utils::CheckersError;
+
+// Number of consecutive queen moves without a capture or a pawn move after which the game is drawn.
+pub const IDLE_LIMIT: u8 = 8;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameStatus {
+    InProgress,
+    Finished(GameResult),
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameState {
+    board: Board,
+    side_to_move: CheckersColor,
+    idle_white: u8,
+    idle_black: u8,
+    history: Vec<GameMove>,
+    snapshots: Vec<(Board, u8, u8)>,
+    outcome: Option<GameResult>,
+}
+
+impl GameState {
+    // Panics when pawn_rows is not between 1 and 3, like Board::new.
+    pub fn new(pawn_rows: usize) -> Self {
+        Self::from_position(Board::new(pawn_rows), CheckersColor::White)
+    }
+
+    pub fn try_new(pawn_rows: usize) -> Result<Self, CheckersError> {
+        Ok(Self::from_position(Board::try_new(pawn_rows)?, CheckersColor::White))
+    }
+
+    pub fn from_position(board: Board, side_to_move: CheckersColor) -> Self {
+        Self {
+            board,
+            side_to_move,
+            idle_white: 0,
+            idle_black: 0,
+            history: Vec::new(),
+            snapshots: Vec::new(),
+            outcome: None,
+        }
+    }
+
+    pub(crate) fn restore(board: Board, side_to_move: CheckersColor, idle: (u8, u8), history: Vec<GameMove>, snapshots: Vec<(Board, u8, u8)>) -> Self {
+        Self {
+            board,
+            side_to_move,
+            idle_white: idle.0,
+            idle_black: idle.1,
+            history,
+            snapshots,
+            outcome: None,
+        }
+    }
+
+    pub fn board(&self) -> Board {
+        self.board
+    }
+
+    pub fn side_to_move(&self) -> CheckersColor {
+        self.side_to_move
+    }
+
+    pub fn history(&self) -> &[GameMove] {
+        &self.history
+    }
+
+    pub(crate) fn snapshots(&self) -> &[(Board, u8, u8)] {
+        &self.snapshots
+    }
+
+    pub fn idle_counters(&self) -> (u8, u8) {
+        (self.idle_white, self.idle_black)
+    }
+
+    pub fn legal_moves(&self) -> Vec<GameMove> {
+        if self.outcome.is_some() {
+            return Vec::new();
+        }
+        MoveExecutor::get_legal_moves(&self.board, self.side_to_move)
+    }
+
+    pub fn status(&self) -> GameStatus {
+        if let Some(outcome) = self.outcome {
+            return GameStatus::Finished(outcome);
+        }
+        if MoveExecutor::has_game_ended(self.board, self.side_to_move) {
+            return GameStatus::Finished(GameResult::Win(self.side_to_move.opposite_color()));
+        }
+        if self.idle_white >= IDLE_LIMIT || self.idle_black >= IDLE_LIMIT {
+            return GameStatus::Finished(GameResult::Draw);
+        }
+        GameStatus::InProgress
+    }
+
+    pub fn apply(&mut self, game_move: &GameMove) -> Result<(), CheckersError> {
+        if let GameStatus::Finished(result) = self.status() {
+            return Err(CheckersError::GameOver(result.to_string()));
+        }
+        let new_board = MoveExecutor::execute_checked(&self.board, self.side_to_move, game_move)?;
+        self.snapshots.push((self.board, self.idle_white, self.idle_black));
+        if let GameMove::Simple(mov) = game_move {
+            match self.board.get_at(mov.x_start, mov.y_start) {
+                Ok(Some(Piece::Queen(CheckersColor::White))) => self.idle_white += 1,
+                Ok(Some(Piece::Queen(CheckersColor::Black))) => self.idle_black += 1,
+                Ok(Some(Piece::Pawn(CheckersColor::White))) => self.idle_white = 0,
+                Ok(Some(Piece::Pawn(CheckersColor::Black))) => self.idle_black = 0,
+                _ => {}
+            }
+        }
+        self.board = MoveExecutor::promote_to_queen(&new_board);
+        self.history.push(game_move.clone());
+        self.side_to_move = self.side_to_move.opposite_color();
+        Ok(())
+    }
+
+    pub fn undo(&mut self) -> Option<GameMove> {
+        let (board, idle_white, idle_black) = self.snapshots.pop()?;
+        self.board = board;
+        self.idle_white = idle_white;
+        self.idle_black = idle_black;
+        self.side_to_move = self.side_to_move.opposite_color();
+        self.outcome = None;
+        self.history.pop()
+    }
+
+    pub fn resign(&mut self, color: CheckersColor) {
+        self.outcome = Some(GameResult::Win(color.opposite_color()));
+    }
+
+    pub fn agree_draw(&mut self) {
+        self.outcome = Some(GameResult::Draw);
+    }
+}
@@ -0,0 +1,265 @@
+use std::cell::Cell;
+use crate::{alias_from_coordinates, Board, CheckersColor, CountEstimator, MinMaxBot};
+use crate::analysis::{format_score, Analysis};
+use crate::board_estimators::Estimator;
+use crate::checkers_utils::CheckersError;
+use crate::col;
+use crate::col::colored_text;
+use crate::moves::{GameMove, Jump, SimpleMove};
+use crate::notation;
+use crate::players::{Decision, Player};
+
+// None when the input is closed or cannot be read.
+fn get_correct_input<T>(list: &[T]) -> Option<usize> {
+    use std::io::{stdin, stdout, Write};
+    loop {
+        let mut s = String::new();
+        let _ = stdout().flush();
+        match stdin().read_line(&mut s) {
+            Ok(0) | Err(_) => return None,
+            Ok(_) => {}
+        }
+        match s.trim_end_matches(['\n', '\r']).parse::<usize>() {
+            Ok(num) if num < list.len() => return Some(num),
+            _ => println!("{} Valid options are between 0 and {}", colored_text("Incorrect input.", col::fg::RED, col::NONE, true), list.len() - 1),
+        }
+    }
+}
+
+
+const HUMAN_HELP: &str = "Type a move as squares, e.g. c3-d4, c3xe5xg3 or 22-18, or its number from the list.
+Commands:
+  undo    take back your last move
+  resign  give up the game
+  draw    offer a draw to the opponent
+  hint    suggest a move, or show the next hint of a puzzle
+  help    show this message";
+
+fn print_error(message: &str) {
+    println!("{} {}", colored_text("Incorrect input.", col::fg::RED, col::NONE, true), message);
+}
+
+pub struct Human<'a> {
+    name: String,
+    color: CheckersColor,
+    hints: Vec<String>,
+    hints_shown: Cell<usize>,
+    hint_depth: usize,
+    // Largest score drop, in pawns, that is played without a warning, scaled by the pawn value of the estimator.
+    blunder_threshold: Option<i32>,
+    // Scores the moves for hints and the blunder check, a CountEstimator when not set.
+    estimator: Option<&'a dyn Estimator>,
+}
+
+impl <'a> Human<'a> {
+    pub fn new(name: &str, color: CheckersColor) -> Self {
+        Self {
+            name: String::from(name),
+            color,
+            hints: Vec::new(),
+            hints_shown: Cell::new(0),
+            hint_depth: 4,
+            blunder_threshold: None,
+            estimator: None,
+        }
+    }
+
+    pub fn set_estimator(&mut self, estimator: &'a dyn Estimator) {
+        self.estimator = Some(estimator);
+    }
+
+    // Depth of the search behind hints and the blunder check.
+    pub fn set_hint_depth(&mut self, depth: usize) {
+        self.hint_depth = depth.max(1);
+    }
+
+    pub fn set_blunder_check(&mut self, threshold: Option<i32>) {
+        self.blunder_threshold = threshold;
+    }
+
+    // Hints for the next move, shown one at a time by the hint command.
+    pub fn set_hints(&mut self, hints: Vec<String>) {
+        self.hints = hints;
+        self.hints_shown.set(0);
+    }
+
+    pub fn hints_shown(&self) -> usize {
+        self.hints_shown.get()
+    }
+
+    // The analysis and the pawn value of its estimator.
+    fn analyse(&self, board: Board) -> (Analysis, i32) {
+        let count_estimator = CountEstimator::new(1, 3);
+        let estimator = self.estimator.unwrap_or(&count_estimator);
+        (MinMaxBot::new("hint", self.color, self.hint_depth, estimator).analyse(board, self.color), estimator.pawn_value())
+    }
+
+    fn decide(&self, moves: &[GameMove], board: Board) -> Decision {
+        use std::io::{stdin, stdout, Write};
+        println!("{}", colored_text(format!("\nPlayer {} moves", self.name).as_str(), col::fg::color(153, 255, 51).as_str(), col::NONE, true));
+        for (i, game_move) in moves.iter().enumerate() {
+            println!("{}. {} ({})", i, game_move, notation::move_to_pdn(game_move));
+        }
+        loop {
+            print!("> ");
+            let _ = stdout().flush();
+            let mut line = String::new();
+            // Without input the game cannot go on, the player resigns.
+            match stdin().read_line(&mut line) {
+                Ok(0) | Err(_) => return Decision::Resign,
+                Ok(_) => {}
+            }
+            match line.trim().to_lowercase().as_str() {
+                "" => {}
+                "help" | "?" => println!("{}", HUMAN_HELP),
+                "undo" => return Decision::Undo,
+                "resign" => return Decision::Resign,
+                "draw" => return Decision::OfferDraw,
+                "hint" => self.print_hint(moves, board),
+                input => match Self::matching_moves(input, moves) {
+                    Ok(matching) => {
+                        let index = if matching.len() == 1 {
+                            matching[0]
+                        } else {
+                            match Self::choose_ambiguous(&matching, moves) {
+                                Some(index) => index,
+                                None => return Decision::Resign,
+                            }
+                        };
+                        if self.confirm(&moves[index], board) {
+                            return Decision::Move(index);
+                        }
+                    }
+                    Err(err) => print_error(&err.to_string()),
+                },
+            }
+        }
+    }
+
+    fn choose_ambiguous(matching: &[usize], moves: &[GameMove]) -> Option<usize> {
+        println!("The move is ambiguous, choose one:");
+        for (i, &index) in matching.iter().enumerate() {
+            println!("{}. {}", i, moves[index]);
+        }
+        get_correct_input(matching).map(|i| matching[i])
+    }
+
+    // The move typed by the player, a move matching several of the moves is an AmbiguousMove error.
+    pub fn parse_move(input: &str, moves: &[GameMove]) -> Result<usize, CheckersError> {
+        let matching = Self::matching_moves(input, moves)?;
+        if matching.len() > 1 {
+            return Err(CheckersError::AmbiguousMove(input.to_string()));
+        }
+        Ok(matching[0])
+    }
+
+    // Indices of all moves matching the input, there is at least one.
+    pub fn matching_moves(input: &str, moves: &[GameMove]) -> Result<Vec<usize>, CheckersError> {
+        if let Ok(index) = input.parse::<usize>() {
+            if index < moves.len() {
+                return Ok(vec![index]);
+            }
+            return Err(CheckersError::notation(input, "there is no move with this number"));
+        }
+        let squares = input.split(|c: char| c == '-' || c == 'x' || c == ':' || c.is_whitespace())
+            .filter(|token| !token.is_empty())
+            .map(notation::parse_square)
+            .collect::<Result<Vec<(usize, usize)>, _>>()
+            .map_err(|_| CheckersError::notation(input, "unknown square or command, type help for help"))?;
+        if squares.len() < 2 {
+            return Err(CheckersError::notation(input, "a move needs a start and a target square"));
+        }
+        let matching = notation::moves_matching_path(moves, &squares);
+        if matching.is_empty() {
+            return Err(CheckersError::IllegalMove(input.to_string()));
+        }
+        Ok(matching)
+    }
+
+    // With the blunder check on, a move that scores worse than the best one by more than the threshold
+    // gets a warning with the refutation line.
+    pub fn blunder_warning(&self, game_move: &GameMove, board: Board) -> Option<String> {
+        let threshold = self.blunder_threshold?;
+        let (analysis, pawn_value) = self.analyse(board);
+        let best = analysis.best()?;
+        let chosen = analysis.moves.iter().find(|m| m.game_move == *game_move)?;
+        if best.score.saturating_sub(chosen.score) <= threshold.saturating_mul(pawn_value) {
+            return None;
+        }
+        let mut warning = format!("{} scores {} while {} scores {}.", notation::move_to_pdn(game_move), format_score(chosen.score),
+                                  notation::move_to_pdn(&best.game_move), format_score(best.score));
+        if !chosen.line.is_empty() {
+            let refutation: Vec<String> = chosen.line.iter().map(notation::move_to_pdn).collect();
+            warning = format!("{}\nRefutation: {}", warning, refutation.join(" "));
+        }
+        Some(warning)
+    }
+
+    fn confirm(&self, game_move: &GameMove, board: Board) -> bool {
+        use std::io::{stdin, stdout, Write};
+        let warning = match self.blunder_warning(game_move, board) {
+            Some(warning) => warning,
+            None => return true,
+        };
+        println!("{} {}", colored_text("Careful!", col::fg::RED, col::NONE, true), warning);
+        print!("Play it anyway? [y/n] ");
+        let _ = stdout().flush();
+        let mut line = String::new();
+        let _ = stdin().read_line(&mut line);
+        matches!(line.trim().to_lowercase().as_str(), "y" | "yes")
+    }
+
+    fn print_hint(&self, moves: &[GameMove], board: Board) {
+        let shown = self.hints_shown.get();
+        if let Some(hint) = self.hints.get(shown) {
+            println!("Hint {}/{}: {}", shown + 1, self.hints.len(), hint);
+            self.hints_shown.set(shown + 1);
+            return;
+        }
+        let mut pieces: Vec<String> = moves.iter()
+            .map(|m| alias_from_coordinates(m.start_pair().0, m.start_pair().1).unwrap())
+            .collect();
+        pieces.dedup();
+        if moves[0].is_capture() {
+            println!("Capture is mandatory. Capturing pieces: {}", pieces.join(", "));
+        } else {
+            println!("Pieces that can move: {}", pieces.join(", "));
+        }
+        if let Some(best) = self.analyse(board).0.best() {
+            println!("Suggested move: {} ({})", notation::move_to_pdn(&best.game_move), format_score(best.score));
+        }
+    }
+}
+
+impl Player for Human<'_> {
+    fn move_piece(&self, possible_moves: &[SimpleMove], board: Board, _allow_first_random: bool) -> Decision {
+        let moves: Vec<GameMove> = possible_moves.iter().map(|&m| GameMove::Simple(m)).collect();
+        self.decide(&moves, board)
+    }
+
+    fn capture(&self, possible_captures: &[&Vec<Jump>], board: Board, _allow_first_random: bool) -> Decision {
+        let moves: Vec<GameMove> = possible_captures.iter().map(|c| GameMove::Capture(c.to_vec())).collect();
+        self.decide(&moves, board)
+    }
+
+    fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    fn set_color(&mut self, color: CheckersColor) {
+        self.color = color;
+    }
+
+    fn get_color(&self) -> CheckersColor {
+        self.color
+    }
+
+    fn accept_draw(&self, _board: Board) -> bool {
+        use std::io::{stdin, stdout, Write};
+        print!("Player {}, do you accept a draw? [y/n] ", self.name);
+        let _ = stdout().flush();
+        let mut line = String::new();
+        let _ = stdin().read_line(&mut line);
+        matches!(line.trim().to_lowercase().as_str(), "y" | "yes")
+    }
+}
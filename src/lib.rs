@@ -0,0 +1,70 @@
+// The original modules keep their code style, the lints it trips are allowed on them only.
+#[allow(clippy::module_inception, clippy::needless_return)]
+mod colors;
+pub mod pieces;
+#[allow(unused_imports, clippy::manual_is_multiple_of, clippy::assign_op_pattern, clippy::match_like_matches_macro,
+        clippy::precedence, clippy::redundant_pattern_matching, clippy::let_and_return, clippy::explicit_counter_loop,
+        clippy::collapsible_match, clippy::extra_unused_lifetimes, clippy::needless_return)]
+pub mod board;
+#[allow(clippy::manual_range_contains, clippy::manual_unwrap_or_default, clippy::manual_unwrap_or, clippy::explicit_counter_loop,
+        clippy::collapsible_match, clippy::collapsible_if, clippy::clone_on_copy, clippy::type_complexity, clippy::ptr_arg)]
+pub mod checkers_utils;
+pub mod moves;
+pub mod players;
+#[cfg(feature = "terminal")]
+mod human;
+pub mod checkers_game;
+#[allow(unused_imports)]
+pub mod board_estimators;
+pub mod neural;
+pub mod mcts;
+pub mod self_play;
+pub mod training_data;
+pub mod puzzles;
+pub mod review;
+pub mod replay;
+pub mod statistics;
+pub mod logging;
+pub mod notation;
+pub mod analysis;
+#[cfg(feature = "terminal")]
+pub mod tui;
+pub mod diagram;
+pub mod errors;
+pub mod observers;
+pub mod game_state;
+#[cfg(feature = "serde")]
+mod serialization;
+#[cfg(feature = "web")]
+pub mod web;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[allow(unused_variables, clippy::module_inception, clippy::match_like_matches_macro, clippy::precedence,
+        clippy::unnecessary_mut_passed, clippy::useless_vec)]
+mod tests;
+
+pub use crate::board::Board;
+pub use crate::pieces::{CheckersColor, Piece};
+pub use crate::checkers_utils::{alias_from_coordinates, coordinates_from_alias, CheckersError, MoveExecutor};
+pub use crate::moves::{GameMove, Jump, Move, SimpleMove};
+pub use crate::players::{Decision, DummyBot, MinMaxBot, Player};
+#[cfg(feature = "terminal")]
+pub use crate::players::Human;
+pub use crate::board_estimators::{CountEstimator, Estimator};
+pub use crate::neural::NeuralEstimator;
+pub use crate::mcts::{MctsBot, PolicyValueNet};
+pub use crate::checkers_game::{Game, GameResult, SavedGame};
+pub use crate::game_state::{GameState, GameStatus};
+pub use crate::analysis::{Analysis, MoveScore};
+pub use crate::observers::{ConsoleObserver, GameObserver};
+
+use crate::colors::colors as col;
+use crate::CheckersColor::{White, Black};
+#[cfg(not(feature = "wasm"))]
+use std::time::Instant;
+#[cfg(feature = "wasm")]
+use web_time::Instant;
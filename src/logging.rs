@@ -0,0 +1,165 @@
+// The library logs through the log facade: games at info level, bot searches at debug level.
+// The console backend for it is installed by the command line program only, a program using the
+// library keeps its own logger. Filters look like "warn" or "info,checkers::players=debug":
+// a default level followed by levels of modules, the most specific module wins.
+// A game log is a file of its own for every game, written by the GameLog observer.
+use std::cell::{Cell, RefCell};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::str::FromStr;
+use std::sync::OnceLock;
+use log::{LevelFilter, Log, Metadata, Record};
+use crate::{Board, CheckersColor, GameMove, Instant};
+use crate::checkers_game::GameResult;
+use crate::checkers_utils::CheckersError;
+use crate::notation::{fen_from_board, move_to_pdn};
+use crate::observers::GameObserver;
+use crate::statistics::SearchStats;
+
+// Level of the console when no filter is given.
+pub const DEFAULT_FILTER: &str = "warn";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogFilter {
+    default: LevelFilter,
+    modules: Vec<(String, LevelFilter)>,
+}
+
+impl LogFilter {
+    pub fn parse(spec: &str) -> Result<Self, CheckersError> {
+        let mut filter = Self { default: LevelFilter::Warn, modules: Vec::new() };
+        for part in spec.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+            let invalid = || CheckersError::InvalidConfiguration(format!("invalid log filter '{}'", part));
+            match part.split_once('=') {
+                Some((module, level)) => {
+                    let level = LevelFilter::from_str(level.trim()).map_err(|_| invalid())?;
+                    filter.modules.push((module.trim().to_string(), level));
+                }
+                None => filter.default = LevelFilter::from_str(part).map_err(|_| invalid())?,
+            }
+        }
+        Ok(filter)
+    }
+
+    pub fn level(&self, target: &str) -> LevelFilter {
+        self.modules.iter()
+            .filter(|(module, _)| target == module || target.starts_with(&format!("{}::", module)))
+            .max_by_key(|(module, _)| module.len())
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default)
+    }
+
+    pub fn max_level(&self) -> LevelFilter {
+        self.modules.iter().map(|(_, level)| *level).fold(self.default, Ord::max)
+    }
+
+    pub fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level(metadata.target())
+    }
+}
+
+struct Logger {
+    console: LogFilter,
+}
+
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.console.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("[{} {}] {}", record.level(), record.target(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+// Installs the console logger, for the command line program. Only the first call has an effect.
+pub fn init(console: LogFilter) {
+    let mut installed = false;
+    let logger = LOGGER.get_or_init(|| {
+        installed = true;
+        Logger { console }
+    });
+    if installed && log::set_logger(logger).is_ok() {
+        log::set_max_level(logger.console.max_level());
+    }
+}
+
+// Writes the events of one game and the searches of its bots to a file, with the time since the
+// start of the log. The file is complete when the log is dropped.
+pub struct GameLog {
+    writer: RefCell<BufWriter<File>>,
+    white_name: String,
+    black_name: String,
+    start: Instant,
+    last_event: Cell<Instant>,
+    plies: Cell<usize>,
+}
+
+impl GameLog {
+    // plies is the number of plies played before the log starts, for a resumed game.
+    pub fn create(path: &str, white_name: &str, black_name: &str, plies: usize) -> Result<Self, CheckersError> {
+        let file = File::create(path)?;
+        let start = Instant::now();
+        Ok(Self {
+            writer: RefCell::new(BufWriter::new(file)),
+            white_name: white_name.to_string(),
+            black_name: black_name.to_string(),
+            start,
+            last_event: Cell::new(start),
+            plies: Cell::new(plies),
+        })
+    }
+
+    fn name(&self, color: CheckersColor) -> &str {
+        match color {
+            CheckersColor::White => &self.white_name,
+            CheckersColor::Black => &self.black_name,
+        }
+    }
+
+    fn write(&self, message: &str) {
+        let elapsed = self.start.elapsed();
+        let _ = writeln!(self.writer.borrow_mut(), "{:>5}.{:03} {}", elapsed.as_secs(), elapsed.subsec_millis(), message);
+    }
+}
+
+impl GameObserver for GameLog {
+    fn on_game_start(&self, board: &Board, side_to_move: CheckersColor) {
+        self.write(&format!("Game started: {} (White) against {} (Black) from {}", self.white_name, self.black_name,
+                            fen_from_board(board, side_to_move)));
+        self.last_event.set(Instant::now());
+    }
+
+    fn on_move(&self, color: CheckersColor, _board_before: &Board, game_move: &GameMove, _board_after: &Board) {
+        self.plies.set(self.plies.get() + 1);
+        self.write(&format!("Ply {}: {:?} {} played {} in {:?}", self.plies.get(), color, self.name(color),
+                            move_to_pdn(game_move), self.last_event.get().elapsed()));
+        self.last_event.set(Instant::now());
+    }
+
+    fn on_undo(&self, _board: &Board, side_to_move: CheckersColor) {
+        self.plies.set(self.plies.get().saturating_sub(2));
+        self.write(&format!("{:?} took back the last two plies", side_to_move));
+        self.last_event.set(Instant::now());
+    }
+
+    fn on_draw_declined(&self, offered_by: CheckersColor) {
+        self.write(&format!("{:?} offered a draw, {} declined", offered_by, self.name(offered_by.opposite_color())));
+    }
+
+    fn on_search(&self, color: CheckersColor, stats: &SearchStats) {
+        self.write(&format!("{} ({:?}) searched {} nodes, {} leaf evaluations, depth {} in {:?}", self.name(color), color,
+                            stats.nodes, stats.leaf_evaluations, stats.depth_reached, stats.elapsed));
+    }
+
+    fn on_game_end(&self, _board: &Board, result: GameResult) {
+        self.write(&format!("Game over: {} after {} plies", result, self.plies.get()));
+        let _ = self.writer.borrow_mut().flush();
+    }
+}
@@ -0,0 +1,353 @@
+use std::fs;
+use log::debug;
+use rand::Rng;
+use crate::{Board, Instant, CheckersColor, GameMove, Jump, MoveExecutor, SimpleMove};
+use crate::checkers_utils::CheckersError;
+use crate::neural::{format_error, relu_backward, Layer, INPUTS};
+use crate::notation::{move_to_pdn, square_number};
+use crate::players::{Decision, Player};
+use crate::self_play::PolicySample;
+
+// One policy output per (start square, end square) pair.
+pub const POLICY_SIZE: usize = 32 * 32;
+const HEADER: &str = "checkers-policy-value 1";
+
+// The network always sees the position from the side to move: for Black the board is turned around
+// and the colours are swapped, so that the pieces to move are "white" and move up the board.
+fn oriented(square: usize, color: CheckersColor) -> usize {
+    match color {
+        CheckersColor::White => square,
+        CheckersColor::Black => 31 - square,
+    }
+}
+
+fn perspective_inputs(board: &Board, color: CheckersColor) -> Vec<usize> {
+    let bits = board.get_board();
+    (0..32).filter_map(|square| {
+        let kind = match ((bits >> (4 * square)) & 0b1111) as u8 {
+            Board::WHITE_PAWN => 0,
+            Board::WHITE_QUEEN => 1,
+            Board::BLACK_PAWN => 2,
+            Board::BLACK_QUEEN => 3,
+            _ => return None,
+        };
+        let kind = if color == CheckersColor::Black { kind ^ 2 } else { kind };
+        Some(oriented(square, color) * 4 + kind)
+    }).collect()
+}
+
+pub fn policy_index(game_move: &GameMove, color: CheckersColor) -> usize {
+    let (start, end) = (game_move.start_pair(), game_move.end_pair());
+    let start = oriented(square_number(start.0, start.1).unwrap() - 1, color);
+    let end = oriented(square_number(end.0, end.1).unwrap() - 1, color);
+    start * 32 + end
+}
+
+fn softmax(logits: &[f32]) -> Vec<f32> {
+    let max = logits.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+    let exps: Vec<f32> = logits.iter().map(|l| (l - max).exp()).collect();
+    let sum: f32 = exps.iter().sum();
+    exps.iter().map(|e| e / sum).collect()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PolicyValueNet {
+    trunk: Vec<Layer>,
+    policy: Layer,
+    value: Layer,
+}
+
+impl PolicyValueNet {
+    pub fn new<R: Rng>(hidden: &[usize], rng: &mut R) -> Self {
+        assert!(!hidden.is_empty(), "The policy/value network needs at least one hidden layer");
+        let sizes: Vec<usize> = std::iter::once(INPUTS).chain(hidden.iter().copied()).collect();
+        let last = *hidden.last().unwrap();
+        Self {
+            trunk: sizes.windows(2).map(|w| Layer::random(w[0], w[1], rng)).collect(),
+            policy: Layer::random(last, POLICY_SIZE, rng),
+            value: Layer::random(last, 1, rng),
+        }
+    }
+
+    // Activations of the trunk, the first entry is the dense input.
+    fn trunk_forward(&self, board: &Board, color: CheckersColor, dense_input: bool) -> Vec<Vec<f32>> {
+        let active = perspective_inputs(board, color);
+        let mut activations = Vec::with_capacity(self.trunk.len() + 1);
+        if dense_input {
+            let mut input = vec![0.0; INPUTS];
+            for &i in &active {
+                input[i] = 1.0;
+            }
+            activations.push(input);
+        } else {
+            activations.push(Vec::new());
+        }
+        for (i, layer) in self.trunk.iter().enumerate() {
+            let mut values = if i == 0 { layer.forward_sparse(&active) } else { layer.forward(&activations[i]) };
+            for value in values.iter_mut() {
+                *value = value.max(0.0);
+            }
+            activations.push(values);
+        }
+        activations
+    }
+
+    // Prior probabilities of the given legal moves and the value of the position for the side to move.
+    pub fn predict(&self, board: &Board, color: CheckersColor, moves: &[GameMove]) -> (Vec<f32>, f32) {
+        let activations = self.trunk_forward(board, color, false);
+        let hidden = activations.last().unwrap();
+        let value = self.value.forward(hidden)[0].tanh();
+        if moves.is_empty() {
+            return (Vec::new(), value);
+        }
+        let logits = self.policy.forward(hidden);
+        let indices: Vec<usize> = moves.iter().map(|m| policy_index(m, color)).collect();
+        let mut unique = indices.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        let probabilities = softmax(&unique.iter().map(|&i| logits[i]).collect::<Vec<f32>>());
+        // moves with the same start and end square share the probability of their policy output
+        let priors = indices.iter().map(|index| {
+            let position = unique.binary_search(index).unwrap();
+            let sharing = indices.iter().filter(|&i| i == index).count();
+            probabilities[position] / sharing as f32
+        }).collect();
+        (priors, value)
+    }
+
+    // One step of stochastic gradient descent, returns the value loss (squared error)
+    // and the policy loss (cross-entropy) before the update.
+    pub fn train_sample(&mut self, sample: &PolicySample, learning_rate: f32) -> (f32, f32) {
+        let activations = self.trunk_forward(&sample.board, sample.side_to_move, true);
+        let hidden = activations.last().unwrap();
+        let value = self.value.forward(hidden)[0].tanh();
+        let value_error = value - sample.value;
+        let value_delta = [2.0 * value_error * (1.0 - value * value)];
+
+        let logits = self.policy.forward(hidden);
+        let probabilities = softmax(&sample.policy.iter().map(|&(i, _)| logits[i]).collect::<Vec<f32>>());
+        let mut policy_delta = vec![0.0; POLICY_SIZE];
+        let mut policy_loss = 0.0;
+        for (&(index, target), &p) in sample.policy.iter().zip(&probabilities) {
+            policy_delta[index] = p - target;
+            policy_loss -= target * p.max(1e-7).ln();
+        }
+
+        let mut delta = self.value.backward(hidden, &value_delta, learning_rate);
+        for (d, p) in delta.iter_mut().zip(self.policy.backward(hidden, &policy_delta, learning_rate)) {
+            *d += p;
+        }
+        for (i, layer) in self.trunk.iter_mut().enumerate().rev() {
+            relu_backward(&mut delta, &activations[i + 1]);
+            delta = layer.backward(&activations[i], &delta, learning_rate);
+        }
+        (value_error * value_error, policy_loss)
+    }
+
+    pub fn load(path: &str) -> Result<Self, CheckersError> {
+        Self::from_text(&fs::read_to_string(path)?)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), CheckersError> {
+        Ok(fs::write(path, self.to_text())?)
+    }
+
+    // Same layout as the value network file: the trunk layers, then the policy and the value head.
+    pub fn to_text(&self) -> String {
+        let mut sizes = vec![INPUTS];
+        sizes.extend(self.trunk.iter().map(|l| l.outputs()));
+        let sizes: Vec<String> = sizes.iter().map(|s| s.to_string()).collect();
+        let mut lines = vec![HEADER.to_string(), format!("trunk {}", sizes.join(" "))];
+        for layer in self.trunk.iter().chain([&self.policy, &self.value]) {
+            layer.write(&mut lines);
+        }
+        lines.join("\n") + "\n"
+    }
+
+    pub fn from_text(text: &str) -> Result<Self, CheckersError> {
+        let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+        if lines.next().map(str::trim) != Some(HEADER) {
+            return Err(format_error("missing header"));
+        }
+        let sizes = lines.next()
+            .and_then(|line| line.strip_prefix("trunk "))
+            .ok_or_else(|| format_error("missing trunk sizes"))?
+            .split_whitespace()
+            .map(|size| size.parse::<usize>().map_err(|_| format_error("invalid layer size")))
+            .collect::<Result<Vec<usize>, CheckersError>>()?;
+        if sizes.len() < 2 || sizes[0] != INPUTS || sizes.contains(&0) {
+            return Err(format_error(&format!("the trunk should start with {} inputs and have a hidden layer", INPUTS)));
+        }
+        let trunk = sizes.windows(2)
+            .map(|w| Layer::read(&mut lines, w[0], w[1]))
+            .collect::<Result<Vec<Layer>, CheckersError>>()?;
+        let last = *sizes.last().unwrap();
+        let policy = Layer::read(&mut lines, last, POLICY_SIZE)?;
+        let value = Layer::read(&mut lines, last, 1)?;
+        if lines.next().is_some() {
+            return Err(format_error("unexpected data after the value head"));
+        }
+        Ok(Self { trunk, policy, value })
+    }
+}
+
+struct Node {
+    moves: Vec<GameMove>,
+    boards: Vec<Board>,
+    priors: Vec<f32>,
+    visits: Vec<u32>,
+    // sum of the values of every move for the side to move in this node
+    values: Vec<f32>,
+    children: Vec<Option<usize>>,
+}
+
+pub struct Mcts<'a> {
+    net: &'a PolicyValueNet,
+    pub simulations: usize,
+    pub exploration: f32,
+}
+
+impl<'a> Mcts<'a> {
+    pub fn new(net: &'a PolicyValueNet, simulations: usize) -> Self {
+        Self {
+            net,
+            simulations,
+            exploration: 1.5,
+        }
+    }
+
+    // Expands a position, returns the node and the value of the position for its side to move.
+    fn expand(&self, board: Board, color: CheckersColor) -> (Node, f32) {
+        let moves = MoveExecutor::get_legal_moves(&board, color);
+        let (priors, value) = self.net.predict(&board, color, &moves);
+        // no legal moves means the side to move has lost
+        let value = if moves.is_empty() { -1.0 } else { value };
+        let boards = moves.iter().map(|m| MoveExecutor::promote_to_queen(&MoveExecutor::execute(&board, m))).collect();
+        let count = moves.len();
+        let node = Node {
+            moves,
+            boards,
+            priors,
+            visits: vec![0; count],
+            values: vec![0.0; count],
+            children: vec![None; count],
+        };
+        (node, value)
+    }
+
+    fn select(&self, node: &Node) -> usize {
+        let total: u32 = node.visits.iter().sum();
+        let scale = self.exploration * (total.max(1) as f32).sqrt();
+        (0..node.moves.len()).max_by(|&a, &b| {
+            let score = |i: usize| {
+                let q = if node.visits[i] == 0 { 0.0 } else { node.values[i] / node.visits[i] as f32 };
+                q + scale * node.priors[i] / (1.0 + node.visits[i] as f32)
+            };
+            score(a).total_cmp(&score(b))
+        }).unwrap()
+    }
+
+    // Legal moves of the position with the number of visits each got during the search.
+    pub fn search(&self, board: Board, color: CheckersColor) -> Vec<(GameMove, u32)> {
+        let (root, _) = self.expand(board, color);
+        let mut nodes = vec![root];
+        if nodes[0].moves.is_empty() {
+            return Vec::new();
+        }
+        for _ in 0..self.simulations {
+            let mut path = Vec::new();
+            let mut current = 0;
+            let mut side = color;
+            let mut value = loop {
+                if nodes[current].moves.is_empty() {
+                    break -1.0;
+                }
+                let choice = self.select(&nodes[current]);
+                path.push((current, choice));
+                side = side.opposite_color();
+                match nodes[current].children[choice] {
+                    Some(child) => current = child,
+                    None => {
+                        let (child, value) = self.expand(nodes[current].boards[choice], side);
+                        nodes.push(child);
+                        let index = nodes.len() - 1;
+                        nodes[current].children[choice] = Some(index);
+                        break value;
+                    }
+                }
+            };
+            // value is for the side to move in the last position, the move leading there was played by the opponent
+            for &(node, choice) in path.iter().rev() {
+                value = -value;
+                nodes[node].visits[choice] += 1;
+                nodes[node].values[choice] += value;
+            }
+        }
+        let root = nodes.swap_remove(0);
+        root.moves.into_iter().zip(root.visits).collect()
+    }
+
+    pub fn best_move(&self, board: Board, color: CheckersColor) -> Option<GameMove> {
+        self.search(board, color).into_iter()
+            .max_by_key(|(_, visits)| *visits)
+            .map(|(game_move, _)| game_move)
+    }
+}
+
+pub struct MctsBot {
+    name: String,
+    color: CheckersColor,
+    net: PolicyValueNet,
+    simulations: usize,
+}
+
+impl MctsBot {
+    pub fn new(name: &str, color: CheckersColor, net: PolicyValueNet, simulations: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            color,
+            net,
+            simulations,
+        }
+    }
+
+    fn choose(&self, moves: &[GameMove], board: Board, allow_first_random: bool) -> Decision {
+        let mut rng = rand::thread_rng();
+        if allow_first_random || moves.len() == 1 {
+            return Decision::Move(rng.gen_range(0..moves.len()));
+        }
+        let start = Instant::now();
+        let visits = Mcts::new(&self.net, self.simulations).search(board, self.color);
+        let best = visits.iter().max_by_key(|(_, count)| *count);
+        if let Some((game_move, count)) = best {
+            debug!("{} ({:?}) ran {} simulations in {:?}, {} visits of {}", self.name, self.color, self.simulations,
+                   start.elapsed(), count, move_to_pdn(game_move));
+        }
+        let index = best.and_then(|(best, _)| moves.iter().position(|m| m == best));
+        Decision::Move(index.unwrap_or(0))
+    }
+}
+
+impl Player for MctsBot {
+    fn move_piece(&self, possible_moves: &[SimpleMove], board: Board, allow_first_random: bool) -> Decision {
+        let moves: Vec<GameMove> = possible_moves.iter().map(|&m| GameMove::Simple(m)).collect();
+        self.choose(&moves, board, allow_first_random)
+    }
+
+    fn capture(&self, possible_captures: &[&Vec<Jump>], board: Board, allow_first_random: bool) -> Decision {
+        let moves: Vec<GameMove> = possible_captures.iter().map(|c| GameMove::Capture(c.to_vec())).collect();
+        self.choose(&moves, board, allow_first_random)
+    }
+
+    fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    fn set_color(&mut self, color: CheckersColor) {
+        self.color = color;
+    }
+
+    fn get_color(&self) -> CheckersColor {
+        self.color
+    }
+}
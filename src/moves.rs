@@ -7,7 +7,8 @@ pub trait Move {
     fn end_pair(&self) -> (usize, usize);
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SimpleMove {
     pub x_start: usize,
     pub y_start: usize,
@@ -17,9 +18,10 @@ pub struct SimpleMove {
 
 impl SimpleMove {
     pub fn new(x_start: usize, y_start: usize, x_end: usize, y_end: usize) -> Result<Self, CheckersError> {
-        if !is_in_bounds(x_start as i32, y_start as i32) ||
-            !is_in_bounds(x_end as i32, y_end as i32) {
-            return Err(CheckersError::IndexOutOfBounds);
+        for (x, y) in [(x_start, y_start), (x_end, y_end)] {
+            if !is_in_bounds(x as i32, y as i32) {
+                return Err(CheckersError::IndexOutOfBounds { x, y });
+            }
         }
         Ok(SimpleMove {
             x_start,
@@ -48,7 +50,8 @@ impl Display for SimpleMove {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Jump {
     pub x_start: usize,
     pub y_start: usize,
@@ -60,10 +63,10 @@ pub struct Jump {
 
 impl Jump {
     pub fn new(x_start: usize, y_start: usize, x_end: usize, y_end: usize, x_capture: usize, y_capture: usize) -> Result<Self, CheckersError> {
-        if !is_in_bounds(x_start as i32, y_start as i32) ||
-            !is_in_bounds(x_end as i32, y_end as i32) ||
-            !is_in_bounds(x_capture as i32, y_capture as i32) {
-            return Err(CheckersError::IndexOutOfBounds);
+        for (x, y) in [(x_start, y_start), (x_end, y_end), (x_capture, y_capture)] {
+            if !is_in_bounds(x as i32, y as i32) {
+                return Err(CheckersError::IndexOutOfBounds { x, y });
+            }
         }
         Ok(Jump {
             x_start,
@@ -93,4 +96,71 @@ impl Display for Jump {
                alias_from_coordinates(self.x_end, self.y_end).unwrap(),
                alias_from_coordinates(self.x_capture, self.y_capture).unwrap())
     }
-}
\ No newline at end of file
+}
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameMove {
+    Simple(SimpleMove),
+    Capture(Vec<Jump>),
+}
+
+impl GameMove {
+    // A capture of the given jumps, each starting where the previous one ends.
+    pub fn try_capture(jumps: Vec<Jump>) -> Result<Self, CheckersError> {
+        if jumps.is_empty() {
+            return Err(CheckersError::IllegalMove("empty capture".to_string()));
+        }
+        if jumps.windows(2).any(|pair| pair[0].end_pair() != pair[1].start_pair()) {
+            return Err(CheckersError::IllegalMove(GameMove::Capture(jumps).to_string()));
+        }
+        Ok(GameMove::Capture(jumps))
+    }
+
+    // Panics on a capture without jumps, which try_capture does not build.
+    pub fn start_pair(&self) -> (usize, usize) {
+        match self {
+            GameMove::Simple(mov) => mov.start_pair(),
+            GameMove::Capture(jumps) => jumps.first().unwrap().start_pair(),
+        }
+    }
+
+    // Panics on a capture without jumps, like start_pair.
+    pub fn end_pair(&self) -> (usize, usize) {
+        match self {
+            GameMove::Simple(mov) => mov.end_pair(),
+            GameMove::Capture(jumps) => jumps.last().unwrap().end_pair(),
+        }
+    }
+
+    pub fn path(&self) -> Vec<(usize, usize)> {
+        match self {
+            GameMove::Simple(mov) => vec![mov.start_pair(), mov.end_pair()],
+            GameMove::Capture(jumps) => {
+                let mut path = vec![self.start_pair()];
+                path.extend(jumps.iter().map(|jump| jump.end_pair()));
+                path
+            }
+        }
+    }
+
+    pub fn captured(&self) -> Vec<(usize, usize)> {
+        match self {
+            GameMove::Simple(_) => Vec::new(),
+            GameMove::Capture(jumps) => jumps.iter().map(|jump| (jump.x_capture, jump.y_capture)).collect(),
+        }
+    }
+
+    pub fn is_capture(&self) -> bool {
+        matches!(self, GameMove::Capture(_))
+    }
+}
+
+impl Display for GameMove {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let separator = if self.is_capture() { "x" } else { "-" };
+        let squares: Vec<String> = self.path().iter()
+            .map(|&(x, y)| alias_from_coordinates(x, y).unwrap())
+            .collect();
+        write!(f, "{}", squares.join(separator))
+    }
+}
@@ -0,0 +1,297 @@
+use std::fs;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use crate::{Board, CheckersColor, Decision, GameResult, GameState, GameStatus, MinMaxBot};
+use crate::players;
+use crate::board_estimators::{game_end_score, Estimator};
+use crate::checkers_utils::CheckersError;
+
+// One input per square and piece kind, in the nibble order of Board::get_board.
+pub const INPUTS: usize = 128;
+// Network outputs are in [-1, 1], estimates are scaled to integers.
+pub const SCALE: f32 = 1000.0;
+// The network scores game results rather than material, a pawn is taken as a tenth of a won game.
+pub const PAWN_VALUE: i32 = 100;
+const HEADER: &str = "checkers-neural 1";
+const MAX_GAME_PLIES: usize = 200;
+
+fn input_index(square: usize, nibble: u128) -> Option<usize> {
+    let kind = match nibble as u8 {
+        Board::WHITE_PAWN => 0,
+        Board::WHITE_QUEEN => 1,
+        Board::BLACK_PAWN => 2,
+        Board::BLACK_QUEEN => 3,
+        _ => return None,
+    };
+    Some(square * 4 + kind)
+}
+
+// Indices of the active inputs, there is at most one per square.
+pub(crate) fn active_inputs(board: &Board) -> Vec<usize> {
+    let bits = board.get_board();
+    (0..32).filter_map(|square| input_index(square, (bits >> (4 * square)) & 0b1111)).collect()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Layer {
+    inputs: usize,
+    outputs: usize,
+    // outputs x inputs, row major
+    weights: Vec<f32>,
+    biases: Vec<f32>,
+}
+
+impl Layer {
+    pub(crate) fn random<R: Rng>(inputs: usize, outputs: usize, rng: &mut R) -> Self {
+        let limit = (6.0 / (inputs + outputs) as f32).sqrt();
+        Self {
+            inputs,
+            outputs,
+            weights: (0..inputs * outputs).map(|_| rng.gen_range(-limit..limit)).collect(),
+            biases: vec![0.0; outputs],
+        }
+    }
+
+    pub(crate) fn outputs(&self) -> usize {
+        self.outputs
+    }
+
+    pub(crate) fn forward(&self, input: &[f32]) -> Vec<f32> {
+        self.weights.chunks(self.inputs).zip(&self.biases)
+            .map(|(row, bias)| bias + row.iter().zip(input).map(|(w, x)| w * x).sum::<f32>())
+            .collect()
+    }
+
+    // The first layer sees a one-hot board, so only the columns of the active inputs are summed.
+    pub(crate) fn forward_sparse(&self, active: &[usize]) -> Vec<f32> {
+        self.weights.chunks(self.inputs).zip(&self.biases)
+            .map(|(row, bias)| bias + active.iter().map(|&i| row[i]).sum::<f32>())
+            .collect()
+    }
+
+    // Gradient descent step for the gradient `delta` of the loss with respect to the outputs,
+    // returns the gradient with respect to the inputs.
+    pub(crate) fn backward(&mut self, input: &[f32], delta: &[f32], learning_rate: f32) -> Vec<f32> {
+        let mut previous = vec![0.0; self.inputs];
+        for (o, &d) in delta.iter().enumerate() {
+            if d == 0.0 {
+                continue;
+            }
+            let row = &mut self.weights[o * self.inputs..(o + 1) * self.inputs];
+            for (j, weight) in row.iter_mut().enumerate() {
+                previous[j] += *weight * d;
+                *weight -= learning_rate * d * input[j];
+            }
+            self.biases[o] -= learning_rate * d;
+        }
+        previous
+    }
+
+    pub(crate) fn write(&self, lines: &mut Vec<String>) {
+        let join = |values: &[f32]| values.iter().map(|v| v.to_string()).collect::<Vec<String>>().join(" ");
+        lines.extend(self.weights.chunks(self.inputs).map(join));
+        lines.push(join(&self.biases));
+    }
+
+    pub(crate) fn read<'a, I: Iterator<Item = &'a str>>(lines: &mut I, inputs: usize, outputs: usize) -> Result<Self, CheckersError> {
+        let mut parse_row = |expected: usize| -> Result<Vec<f32>, CheckersError> {
+            let row = lines.next().ok_or_else(|| format_error("unexpected end of file"))?
+                .split_whitespace()
+                .map(|value| value.parse::<f32>().map_err(|_| format_error("invalid number")))
+                .collect::<Result<Vec<f32>, CheckersError>>()?;
+            if row.len() != expected {
+                return Err(format_error(&format!("expected {} values in a row, found {}", expected, row.len())));
+            }
+            Ok(row)
+        };
+        let mut weights = Vec::with_capacity(inputs * outputs);
+        for _ in 0..outputs {
+            weights.extend(parse_row(inputs)?);
+        }
+        let biases = parse_row(outputs)?;
+        Ok(Layer { inputs, outputs, weights, biases })
+    }
+}
+
+pub(crate) fn format_error(message: &str) -> CheckersError {
+    CheckersError::SerializationError(format!("neural network: {}", message))
+}
+
+// Zeroes the gradient of ReLU units that were not active.
+pub(crate) fn relu_backward(delta: &mut [f32], activations: &[f32]) {
+    for (d, &a) in delta.iter_mut().zip(activations) {
+        if a <= 0.0 {
+            *d = 0.0;
+        }
+    }
+}
+
+fn activate(values: &mut [f32], last: bool) {
+    for value in values.iter_mut() {
+        *value = if last { value.tanh() } else { value.max(0.0) };
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NeuralEstimator {
+    layers: Vec<Layer>,
+}
+
+pub struct Sample {
+    pub board: Board,
+    // Final result of the game the position comes from: 1 for a White win, -1 for a Black win, 0 for a draw.
+    pub target: f32,
+}
+
+impl NeuralEstimator {
+    pub fn new<R: Rng>(hidden: &[usize], rng: &mut R) -> Self {
+        let sizes: Vec<usize> = std::iter::once(INPUTS).chain(hidden.iter().copied()).chain(std::iter::once(1)).collect();
+        Self {
+            layers: sizes.windows(2).map(|w| Layer::random(w[0], w[1], rng)).collect(),
+        }
+    }
+
+    // Evaluation from White's point of view, between -1 and 1.
+    pub fn evaluate(&self, board: &Board) -> f32 {
+        let last = self.layers.len() - 1;
+        let mut values = self.layers[0].forward_sparse(&active_inputs(board));
+        activate(&mut values, last == 0);
+        for (i, layer) in self.layers.iter().enumerate().skip(1) {
+            values = layer.forward(&values);
+            activate(&mut values, i == last);
+        }
+        values[0]
+    }
+
+    pub fn load(path: &str) -> Result<Self, CheckersError> {
+        Self::from_text(&fs::read_to_string(path)?)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), CheckersError> {
+        Ok(fs::write(path, self.to_text())?)
+    }
+
+    // Text format: a header line, a line with the layer sizes, then for every layer
+    // one line per output neuron with its weights followed by a line with the biases.
+    pub fn to_text(&self) -> String {
+        let mut sizes = vec![INPUTS];
+        sizes.extend(self.layers.iter().map(|l| l.outputs));
+        let sizes: Vec<String> = sizes.iter().map(|s| s.to_string()).collect();
+        let mut lines = vec![HEADER.to_string(), format!("layers {}", sizes.join(" "))];
+        for layer in &self.layers {
+            layer.write(&mut lines);
+        }
+        lines.join("\n") + "\n"
+    }
+
+    pub fn from_text(text: &str) -> Result<Self, CheckersError> {
+        let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+        if lines.next().map(str::trim) != Some(HEADER) {
+            return Err(format_error("missing header"));
+        }
+        let sizes = lines.next()
+            .and_then(|line| line.strip_prefix("layers "))
+            .ok_or_else(|| format_error("missing layer sizes"))?
+            .split_whitespace()
+            .map(|size| size.parse::<usize>().map_err(|_| format_error("invalid layer size")))
+            .collect::<Result<Vec<usize>, CheckersError>>()?;
+        if sizes.len() < 2 || sizes[0] != INPUTS || sizes[sizes.len() - 1] != 1 || sizes.contains(&0) {
+            return Err(format_error(&format!("layers should go from {} inputs to 1 output", INPUTS)));
+        }
+        let layers = sizes.windows(2)
+            .map(|w| Layer::read(&mut lines, w[0], w[1]))
+            .collect::<Result<Vec<Layer>, CheckersError>>()?;
+        if lines.next().is_some() {
+            return Err(format_error("unexpected data after the last layer"));
+        }
+        Ok(Self { layers })
+    }
+
+    // One epoch of stochastic gradient descent on the squared error, returns the mean loss before the updates.
+    pub fn train_epoch<R: Rng>(&mut self, samples: &[Sample], learning_rate: f32, rng: &mut R) -> f32 {
+        let mut order: Vec<usize> = (0..samples.len()).collect();
+        order.shuffle(rng);
+        let mut total = 0.0;
+        for i in order {
+            total += self.train_sample(&samples[i], learning_rate);
+        }
+        total / samples.len().max(1) as f32
+    }
+
+    fn train_sample(&mut self, sample: &Sample, learning_rate: f32) -> f32 {
+        let last = self.layers.len() - 1;
+        let mut input = vec![0.0; INPUTS];
+        for i in active_inputs(&sample.board) {
+            input[i] = 1.0;
+        }
+        let mut activations = vec![input];
+        for (i, layer) in self.layers.iter().enumerate() {
+            let mut values = layer.forward(activations.last().unwrap());
+            activate(&mut values, i == last);
+            activations.push(values);
+        }
+        let output = activations[last + 1][0];
+        let error = output - sample.target;
+        // gradient of the loss with respect to the pre-activation values of the current layer
+        let mut delta = vec![2.0 * error * (1.0 - output * output)];
+        for (i, layer) in self.layers.iter_mut().enumerate().rev() {
+            delta = layer.backward(&activations[i], &delta, learning_rate);
+            if i > 0 {
+                relu_backward(&mut delta, &activations[i]);
+            }
+        }
+        error * error
+    }
+}
+
+impl Estimator for NeuralEstimator {
+    fn estimate(&self, board: Board, maximising_color: CheckersColor, check_for_endgame: bool) -> i32 {
+        if check_for_endgame {
+            if let Some(score) = game_end_score(board, maximising_color) {
+                return score;
+            }
+        }
+        let score = (self.evaluate(&board) * SCALE) as i32;
+        match maximising_color {
+            CheckersColor::White => score,
+            CheckersColor::Black => -score,
+        }
+    }
+
+    fn pawn_value(&self) -> i32 {
+        PAWN_VALUE
+    }
+}
+
+// Plays games between two MinMaxBots with the given estimator and labels every position with the game result.
+// The first random_plies plies of each game are random so that the games differ.
+pub fn self_play<R: Rng>(games: usize, depth: usize, random_plies: usize, estimator: &dyn Estimator, rng: &mut R) -> Vec<Sample> {
+    let white = MinMaxBot::new("White", CheckersColor::White, depth, estimator);
+    let black = MinMaxBot::new("Black", CheckersColor::Black, depth, estimator);
+    let mut samples = Vec::new();
+    for _ in 0..games {
+        let mut state = GameState::new(3);
+        let mut positions = Vec::new();
+        while state.status() == GameStatus::InProgress && state.history().len() < MAX_GAME_PLIES {
+            let moves = state.legal_moves();
+            let game_move = if state.history().len() < random_plies {
+                moves[rng.gen_range(0..moves.len())].clone()
+            } else {
+                let bot = if state.side_to_move() == CheckersColor::White { &white } else { &black };
+                let Decision::Move(index) = players::decide(bot, state.board(), &moves, false) else {
+                    unreachable!("MinMaxBot always plays a move")
+                };
+                moves[index].clone()
+            };
+            state.apply(&game_move).unwrap();
+            positions.push(state.board());
+        }
+        let target = match state.status() {
+            GameStatus::Finished(GameResult::Win(CheckersColor::White)) => 1.0,
+            GameStatus::Finished(GameResult::Win(CheckersColor::Black)) => -1.0,
+            _ => 0.0,
+        };
+        samples.extend(positions.into_iter().map(|board| Sample { board, target }));
+    }
+    samples
+}
@@ -0,0 +1,288 @@
+use crate::{Board, CheckersColor, MoveExecutor, Piece};
+use crate::checkers_utils::{coordinates_from_alias, CheckersError};
+use crate::moves::GameMove;
+
+// Squares are numbered 1..32 row by row from the top of the board (black side),
+// which matches the order of the fields inside the u128 board.
+
+pub fn square_number(x: usize, y: usize) -> Result<usize, CheckersError> {
+    if x > 7 || y > 7 {
+        return Err(CheckersError::IndexOutOfBounds { x, y });
+    }
+    if (x + y).is_multiple_of(2) {
+        return Err(CheckersError::LightSquare { x, y });
+    }
+    Ok(4 * x + y / 2 + 1)
+}
+
+pub fn coordinates_from_square(square: usize) -> Result<(usize, usize), CheckersError> {
+    if !(1..=32).contains(&square) {
+        return Err(CheckersError::notation(&square.to_string(), "squares are numbered from 1 to 32"));
+    }
+    let x = (square - 1) / 4;
+    let y = 2 * ((square - 1) % 4) + if x.is_multiple_of(2) { 1 } else { 0 };
+    Ok((x, y))
+}
+
+pub fn parse_square(text: &str) -> Result<(usize, usize), CheckersError> {
+    match text.trim().parse::<usize>() {
+        Ok(square) => coordinates_from_square(square),
+        Err(_) => coordinates_from_alias(text),
+    }
+}
+
+pub fn moves_through(moves: &[GameMove], squares: &[(usize, usize)]) -> Vec<usize> {
+    moves.iter().enumerate()
+        .filter(|(_, m)| {
+            let path = m.path();
+            let mut rest = path[1..].iter();
+            path.first() == squares.first() && squares[1..].iter().all(|square| rest.any(|p| p == square))
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+pub fn moves_matching_path(moves: &[GameMove], squares: &[(usize, usize)]) -> Vec<usize> {
+    moves_through(moves, squares).into_iter()
+        .filter(|&i| squares.len() > 1 && Some(&moves[i].end_pair()) == squares.last())
+        .collect()
+}
+
+pub fn board_from_fen(fen: &str) -> Result<(Board, CheckersColor), CheckersError> {
+    let fen = fen.trim().trim_matches('"').trim_end_matches('.');
+    let mut sections = fen.split(':');
+    let color = match sections.next().map(|s| s.trim().to_uppercase()) {
+        Some(s) if s == "W" => CheckersColor::White,
+        Some(s) if s == "B" => CheckersColor::Black,
+        _ => return Err(CheckersError::notation(fen, "a FEN should start with the side to move, W or B")),
+    };
+    let mut board = Board::empty();
+    for section in sections {
+        let section = section.trim();
+        let (pawn, queen) = match section.chars().next() {
+            Some('W') | Some('w') => (Board::WHITE_PAWN, Board::WHITE_QUEEN),
+            Some('B') | Some('b') => (Board::BLACK_PAWN, Board::BLACK_QUEEN),
+            _ => return Err(CheckersError::notation(section, "a piece list should start with W or B")),
+        };
+        for token in section[1..].split(',').map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            let (piece, squares) = match token.strip_prefix('K').or_else(|| token.strip_prefix('k')) {
+                Some(rest) => (queen, rest),
+                None => (pawn, token),
+            };
+            for square in parse_square_range(squares)? {
+                let (x, y) = coordinates_from_square(square)?;
+                board.set_at(x, y, piece)?;
+            }
+        }
+    }
+    Ok((board, color))
+}
+
+fn parse_square_range(text: &str) -> Result<Vec<usize>, CheckersError> {
+    let parse = |s: &str| s.trim().parse::<usize>().map_err(|_| CheckersError::notation(s, "a square should be a number"));
+    match text.split_once('-') {
+        Some((from, to)) => {
+            let (from, to) = (parse(from)?, parse(to)?);
+            if from > to {
+                return Err(CheckersError::notation(text, "the range of squares is reversed"));
+            }
+            Ok((from..=to).collect())
+        }
+        None => Ok(vec![parse(text)?]),
+    }
+}
+
+pub fn fen_from_board(board: &Board, color: CheckersColor) -> String {
+    let mut white = Vec::new();
+    let mut black = Vec::new();
+    for square in 1..=32 {
+        let (x, y) = coordinates_from_square(square).unwrap();
+        match board.get_at(x, y) {
+            Ok(Some(Piece::Pawn(CheckersColor::White))) => white.push(square.to_string()),
+            Ok(Some(Piece::Queen(CheckersColor::White))) => white.push(format!("K{}", square)),
+            Ok(Some(Piece::Pawn(CheckersColor::Black))) => black.push(square.to_string()),
+            Ok(Some(Piece::Queen(CheckersColor::Black))) => black.push(format!("K{}", square)),
+            _ => {}
+        }
+    }
+    let side = match color {
+        CheckersColor::White => "W",
+        CheckersColor::Black => "B",
+    };
+    format!("{}:W{}:B{}", side, white.join(","), black.join(","))
+}
+
+pub fn move_to_pdn(game_move: &GameMove) -> String {
+    let separator = if game_move.is_capture() { "x" } else { "-" };
+    let squares: Vec<String> = game_move.path().iter()
+        .map(|&(x, y)| square_number(x, y).unwrap().to_string())
+        .collect();
+    squares.join(separator)
+}
+
+pub fn move_from_pdn(board: &Board, color: CheckersColor, text: &str) -> Result<GameMove, CheckersError> {
+    let text = text.trim();
+    let is_capture = text.contains('x') || text.contains('X');
+    let squares = text.split(['-', 'x', 'X'])
+        .map(|s| s.trim().parse::<usize>().map_err(|_| CheckersError::notation(text, "squares should be numbers"))
+            .and_then(coordinates_from_square))
+        .collect::<Result<Vec<(usize, usize)>, CheckersError>>()?;
+    if squares.len() < 2 {
+        return Err(CheckersError::notation(text, "a move needs a start and a target square"));
+    }
+    let candidates: Vec<GameMove> = MoveExecutor::get_legal_moves(board, color).into_iter()
+        .filter(|m| m.is_capture() == is_capture)
+        .filter(|m| {
+            let path = m.path();
+            if squares.len() == 2 {
+                path.first() == squares.first() && path.last() == squares.last()
+            } else {
+                path == squares
+            }
+        })
+        .collect();
+    match candidates.len() {
+        1 => Ok(candidates[0].clone()),
+        0 => Err(CheckersError::IllegalMove(text.to_string())),
+        _ => Err(CheckersError::AmbiguousMove(text.to_string())),
+    }
+}
+
+pub struct PdnGame {
+    pub tags: Vec<(String, String)>,
+    pub moves: Vec<String>,
+    pub result: String,
+}
+
+impl PdnGame {
+    pub const RESULTS: [&'static str; 6] = ["2-0", "0-2", "1-1", "1-0", "0-1", "1/2-1/2"];
+
+    pub fn new(start: Option<String>, moves: &[GameMove], result: &str) -> Self {
+        let mut tags = vec![("GameType".to_string(), "26".to_string())];
+        if let Some(fen) = start {
+            tags.push(("FEN".to_string(), fen));
+        }
+        tags.push(("Result".to_string(), result.to_string()));
+        Self {
+            tags,
+            moves: moves.iter().map(move_to_pdn).collect(),
+            result: result.to_string(),
+        }
+    }
+
+    pub fn parse_all(text: &str) -> Result<Vec<PdnGame>, CheckersError> {
+        let mut games = Vec::new();
+        let mut current = PdnGame { tags: Vec::new(), moves: Vec::new(), result: "*".to_string() };
+        let mut has_content = false;
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '[' => {
+                    if !current.moves.is_empty() {
+                        games.push(current);
+                        current = PdnGame { tags: Vec::new(), moves: Vec::new(), result: "*".to_string() };
+                    }
+                    let tag: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                    let (name, value) = tag.trim().split_once(' ')
+                        .ok_or_else(|| CheckersError::notation(&tag, "a tag should have a name and a value"))?;
+                    current.tags.push((name.to_string(), value.trim().trim_matches('"').to_string()));
+                    has_content = true;
+                }
+                '{' => {
+                    chars.by_ref().take_while(|&c| c != '}').for_each(drop);
+                }
+                // variations are skipped, they may be nested
+                '(' => {
+                    let mut level = 1;
+                    for c in chars.by_ref() {
+                        match c {
+                            '(' => level += 1,
+                            ')' => level -= 1,
+                            _ => {}
+                        }
+                        if level == 0 {
+                            break;
+                        }
+                    }
+                }
+                c if c.is_whitespace() => {}
+                _ => {
+                    let mut token = c.to_string();
+                    while let Some(&next) = chars.peek() {
+                        if next.is_whitespace() || next == '{' || next == '[' || next == '(' {
+                            break;
+                        }
+                        token.push(next);
+                        chars.next();
+                    }
+                    has_content = true;
+                    if Self::RESULTS.contains(&token.as_str()) || token == "*" {
+                        current.result = token;
+                        games.push(current);
+                        current = PdnGame { tags: Vec::new(), moves: Vec::new(), result: "*".to_string() };
+                        has_content = false;
+                    } else {
+                        let token = match token.rfind('.') {
+                            Some(i) => &token[i + 1..],
+                            None => token.as_str(),
+                        };
+                        // annotations like 22-18! or 11-15?? are dropped
+                        let token = token.trim_end_matches(['!', '?']);
+                        if token.contains('-') || token.contains('x') {
+                            current.moves.push(token.to_string());
+                        } else if !token.is_empty() {
+                            return Err(CheckersError::notation(token, "expected a move or a result"));
+                        }
+                    }
+                }
+            }
+        }
+        if has_content {
+            games.push(current);
+        }
+        Ok(games)
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find(|(tag, _)| tag == name).map(|(_, value)| value.as_str())
+    }
+
+    pub fn start_position(&self) -> Result<(Board, CheckersColor), CheckersError> {
+        match self.tag("FEN") {
+            Some(fen) => board_from_fen(fen),
+            None => Ok((Board::new(3), CheckersColor::White)),
+        }
+    }
+
+    pub fn game_moves(&self) -> Result<Vec<GameMove>, CheckersError> {
+        let (mut board, mut color) = self.start_position()?;
+        let mut moves = Vec::new();
+        for text in &self.moves {
+            let game_move = move_from_pdn(&board, color, text)?;
+            board = MoveExecutor::promote_to_queen(&MoveExecutor::execute(&board, &game_move));
+            color = color.opposite_color();
+            moves.push(game_move);
+        }
+        Ok(moves)
+    }
+
+    pub fn to_pdn(&self) -> String {
+        let mut ret = String::new();
+        for (name, value) in &self.tags {
+            ret = format!("{}[{} \"{}\"]\n", ret, name, value);
+        }
+        let black_starts = self.tag("FEN").map(|fen| fen.trim_start().starts_with('B')).unwrap_or(false);
+        let mut line = String::new();
+        let first_ply: usize = if black_starts { 1 } else { 0 };
+        if black_starts && !self.moves.is_empty() {
+            line = "1...".to_string();
+        }
+        for (ply, mov) in (first_ply..).zip(self.moves.iter()) {
+            if ply.is_multiple_of(2) {
+                line = format!("{} {}.", line, ply / 2 + 1);
+            }
+            line = format!("{} {}", line, mov);
+        }
+        format!("{}\n{} {}\n", ret, line.trim(), self.result)
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{Board, CheckersColor, GameMove};
+use crate::checkers_game::GameResult;
+use crate::statistics::SearchStats;
+
+pub trait GameObserver {
+    fn on_game_start(&self, _board: &Board, _side_to_move: CheckersColor) {}
+    fn on_move(&self, _color: CheckersColor, _board_before: &Board, _game_move: &GameMove, _board_after: &Board) {}
+    fn on_capture(&self, _color: CheckersColor, _game_move: &GameMove, _captured: &[(usize, usize)]) {}
+    fn on_promotion(&self, _color: CheckersColor, _x: usize, _y: usize) {}
+    fn on_undo(&self, _board: &Board, _side_to_move: CheckersColor) {}
+    // The player asked for an undo before two plies were played.
+    fn on_undo_rejected(&self, _side_to_move: CheckersColor) {}
+    fn on_draw_declined(&self, _offered_by: CheckersColor) {}
+    // The player to move searched for the move it is about to play.
+    fn on_search(&self, _color: CheckersColor, _stats: &SearchStats) {}
+    fn on_game_end(&self, _board: &Board, _result: GameResult) {}
+}
+
+pub struct ConsoleObserver {
+    white_name: String,
+    black_name: String,
+}
+
+impl ConsoleObserver {
+    pub fn new(white_name: &str, black_name: &str) -> Self {
+        Self {
+            white_name: white_name.to_string(),
+            black_name: black_name.to_string(),
+        }
+    }
+
+    fn name(&self, color: CheckersColor) -> &str {
+        match color {
+            CheckersColor::White => &self.white_name,
+            CheckersColor::Black => &self.black_name,
+        }
+    }
+}
+
+impl GameObserver for ConsoleObserver {
+    fn on_game_start(&self, board: &Board, _side_to_move: CheckersColor) {
+        println!("{}", board.repr());
+    }
+
+    fn on_move(&self, _color: CheckersColor, _board_before: &Board, _game_move: &GameMove, board_after: &Board) {
+        println!("{}", board_after.repr());
+    }
+
+    fn on_undo(&self, board: &Board, _side_to_move: CheckersColor) {
+        println!("{}", board.repr());
+    }
+
+    fn on_undo_rejected(&self, _side_to_move: CheckersColor) {
+        println!("Nothing to undo.");
+    }
+
+    fn on_draw_declined(&self, offered_by: CheckersColor) {
+        println!("Player {} declined the draw offer.", self.name(offered_by.opposite_color()));
+    }
+
+    fn on_game_end(&self, _board: &Board, result: GameResult) {
+        match result {
+            GameResult::Win(color) => println!("Player {} win!", self.name(color)),
+            GameResult::Draw => println!("Draw!"),
+        }
+    }
+}
@@ -2,7 +2,8 @@ use std::fmt::{Display, Formatter};
 use crate::Board;
 use crate::colors::colors as col;
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CheckersColor {
     White,
     Black,
@@ -18,6 +19,7 @@ impl CheckersColor {
 }
 
 #[derive(Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Piece {
     Pawn(CheckersColor),
     Queen(CheckersColor),
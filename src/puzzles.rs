@@ -0,0 +1,343 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use crate::{alias_from_coordinates, Board, CheckersColor, GameMove, MoveExecutor};
+use crate::analysis::{self, format_score};
+use crate::board_estimators::{CountEstimator, Estimator};
+use crate::checkers_utils::CheckersError;
+use crate::notation::{fen_from_board, move_to_pdn, PdnGame};
+
+// A position where the side to move wins material (or the game) with a capture combination.
+#[derive(Clone)]
+pub struct Puzzle {
+    pub board: Board,
+    pub side_to_move: CheckersColor,
+    // The winning line, starting with the key move and ending with the last capture of the combination.
+    pub solution: Vec<GameMove>,
+    // Material won in pawns, i32::MAX when the combination wins the game.
+    pub gain: i32,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct PuzzleConfig {
+    // Search depth used to find and verify the combination.
+    pub depth: usize,
+    // Smallest material gain, in pawns, that makes a puzzle.
+    pub min_gain: i32,
+}
+
+impl Default for PuzzleConfig {
+    fn default() -> Self {
+        Self {
+            depth: 5,
+            min_gain: 1,
+        }
+    }
+}
+
+impl Puzzle {
+    // A PDN game with the puzzle position as FEN and the solution as its moves.
+    pub fn to_pdn_game(&self) -> PdnGame {
+        let mut game = PdnGame::new(Some(fen_from_board(&self.board, self.side_to_move)), &self.solution, "*");
+        game.tags.insert(0, ("Event".to_string(), "Puzzle".to_string()));
+        game.tags.push(("Gain".to_string(), format_score(self.gain)));
+        game
+    }
+
+    pub fn from_pdn_game(game: &PdnGame) -> Result<Self, CheckersError> {
+        let (board, side_to_move) = game.start_position()?;
+        let solution = game.game_moves()?;
+        if solution.is_empty() {
+            return Err(CheckersError::SerializationError("puzzle: missing solution".to_string()));
+        }
+        let gain = match game.tag("Gain") {
+            Some("win") => i32::MAX,
+            Some(gain) => gain.parse().map_err(|_| CheckersError::SerializationError(format!("puzzle: invalid gain '{}'", gain)))?,
+            None => 0,
+        };
+        Ok(Self { board, side_to_move, solution, gain })
+    }
+}
+
+// The only move that is strictly better than every other one at the given depth, if there is one.
+fn unique_best(board: Board, color: CheckersColor, depth: usize, estimator: &dyn Estimator) -> Option<GameMove> {
+    let analysis = analysis::analyse(board, color, depth, estimator);
+    let best = analysis.best()?;
+    if analysis.moves.get(1).is_some_and(|second| second.score >= best.score) {
+        return None;
+    }
+    Some(best.game_move.clone())
+}
+
+// Checks whether the position is a puzzle: the side to move has a unique key move that wins at least
+// min_gain pawns through a line with captures, every other move scores at least min_gain pawns less,
+// and each later move of the solver in the combination is again the only best one.
+pub fn find_puzzle(board: Board, color: CheckersColor, config: &PuzzleConfig) -> Option<Puzzle> {
+    let estimator = CountEstimator::new(1, 3);
+    let material = estimator.estimate(board, color, false);
+    if MoveExecutor::get_legal_moves(&board, color).len() < 2 {
+        return None;
+    }
+    let analysis = analysis::analyse(board, color, config.depth, &estimator);
+    let best = analysis.best()?;
+    // win and loss scores are near i32::MAX and i32::MIN
+    let gain = if best.score > i32::MAX / 2 { i32::MAX } else { best.score.saturating_sub(material) };
+    if gain < config.min_gain {
+        return None;
+    }
+    if best.score.saturating_sub(analysis.moves[1].score) < config.min_gain.max(1) {
+        return None;
+    }
+    let mut line = vec![best.game_move.clone()];
+    line.extend(best.line.iter().cloned());
+
+    // the combination ends with the last capture of the line
+    let last_capture = line.iter().rposition(GameMove::is_capture)?;
+    line.truncate(last_capture + 1);
+    let mut current = board;
+    let mut side = color;
+    let mut solver_captures = false;
+    for (ply, game_move) in line.iter().enumerate() {
+        if side == color {
+            solver_captures |= !MoveExecutor::get_all_captures(&current, side).is_empty();
+            if ply > 0 && MoveExecutor::get_legal_moves(&current, side).len() > 1
+                && unique_best(current, side, config.depth - ply, &estimator)? != *game_move {
+                return None;
+            }
+        }
+        current = MoveExecutor::promote_to_queen(&MoveExecutor::execute(&current, game_move));
+        side = side.opposite_color();
+    }
+    if !solver_captures {
+        return None;
+    }
+    Some(Puzzle { board, side_to_move: color, solution: line, gain })
+}
+
+// Every puzzle in the positions of a game, a position is only reported once.
+pub fn find_puzzles(positions: &[(Board, CheckersColor)], config: &PuzzleConfig) -> Vec<Puzzle> {
+    let mut seen = HashSet::new();
+    positions.iter()
+        .filter(|(board, color)| seen.insert((board.get_board(), *color)))
+        .filter_map(|&(board, color)| find_puzzle(board, color, config))
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PuzzleStep {
+    // The move was right and the opponent answered with the given move.
+    Correct(GameMove),
+    Solved,
+    Wrong,
+}
+
+// A puzzle being solved. Moves of the solution are always right; another move is accepted when
+// the search rates it as high as the best one, the opponent's replies then come from the search too.
+pub struct PuzzleSession<'a> {
+    puzzle: &'a Puzzle,
+    board: Board,
+    side_to_move: CheckersColor,
+    plies: usize,
+    on_book: bool,
+    depth: usize,
+    mistakes: usize,
+    estimator: CountEstimator,
+}
+
+impl<'a> PuzzleSession<'a> {
+    pub fn new(puzzle: &'a Puzzle, depth: usize) -> Self {
+        Self {
+            puzzle,
+            board: puzzle.board,
+            side_to_move: puzzle.side_to_move,
+            plies: 0,
+            on_book: true,
+            depth: depth.max(1),
+            mistakes: 0,
+            estimator: CountEstimator::new(1, 3),
+        }
+    }
+
+    pub fn board(&self) -> Board {
+        self.board
+    }
+
+    pub fn side_to_move(&self) -> CheckersColor {
+        self.side_to_move
+    }
+
+    pub fn legal_moves(&self) -> Vec<GameMove> {
+        MoveExecutor::get_legal_moves(&self.board, self.side_to_move)
+    }
+
+    pub fn mistakes(&self) -> usize {
+        self.mistakes
+    }
+
+    // Back to the puzzle position, mistakes are kept.
+    pub fn restart(&mut self) {
+        self.board = self.puzzle.board;
+        self.side_to_move = self.puzzle.side_to_move;
+        self.plies = 0;
+        self.on_book = true;
+    }
+
+    fn best_move(&self) -> Option<GameMove> {
+        if self.on_book {
+            if let Some(game_move) = self.puzzle.solution.get(self.plies) {
+                return Some(game_move.clone());
+            }
+        }
+        analysis::analyse(self.board, self.side_to_move, self.depth, &self.estimator)
+            .best()
+            .map(|best| best.game_move.clone())
+    }
+
+    // Hints for the current move, from vague to the move itself.
+    pub fn hints(&self) -> Vec<String> {
+        match self.best_move() {
+            Some(game_move) => {
+                let (x, y) = game_move.start_pair();
+                vec![
+                    format!("Move the piece on {}.", alias_from_coordinates(x, y).unwrap()),
+                    format!("Play {}.", move_to_pdn(&game_move)),
+                ]
+            }
+            None => Vec::new(),
+        }
+    }
+
+    fn apply(&mut self, game_move: &GameMove) {
+        self.board = MoveExecutor::promote_to_queen(&MoveExecutor::execute(&self.board, game_move));
+        self.side_to_move = self.side_to_move.opposite_color();
+        self.plies += 1;
+    }
+
+    fn is_solved(&self) -> bool {
+        if MoveExecutor::has_game_ended(self.board, self.side_to_move) {
+            return self.side_to_move != self.puzzle.side_to_move;
+        }
+        if self.on_book {
+            return self.plies >= self.puzzle.solution.len();
+        }
+        let gain = self.estimator.estimate(self.board, self.puzzle.side_to_move, false)
+            - self.estimator.estimate(self.puzzle.board, self.puzzle.side_to_move, false);
+        gain >= self.puzzle.gain || self.plies >= self.puzzle.solution.len() + self.depth
+    }
+
+    // Plays a move of the solver and the opponent's answer.
+    pub fn play(&mut self, game_move: &GameMove) -> PuzzleStep {
+        let expected = if self.on_book { self.puzzle.solution.get(self.plies) } else { None };
+        if expected != Some(game_move) {
+            let analysis = analysis::analyse(self.board, self.side_to_move, self.depth, &self.estimator);
+            let score = analysis.moves.iter().find(|m| m.game_move == *game_move).map(|m| m.score);
+            if score.is_none() || score != analysis.score() {
+                self.mistakes += 1;
+                return PuzzleStep::Wrong;
+            }
+            self.on_book = false;
+        }
+        self.apply(game_move);
+        if self.is_solved() {
+            return PuzzleStep::Solved;
+        }
+        let reply = match self.best_move() {
+            Some(reply) => reply,
+            None => return PuzzleStep::Solved,
+        };
+        self.apply(&reply);
+        if self.is_solved() {
+            return PuzzleStep::Solved;
+        }
+        PuzzleStep::Correct(reply)
+    }
+}
+
+// Three points for a clean solution, one less for every hint and mistake, at least one for solving it.
+pub fn puzzle_points(hints: usize, mistakes: usize) -> u32 {
+    3_usize.saturating_sub(hints + mistakes).max(1) as u32
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PuzzleScore {
+    pub fen: String,
+    pub attempts: u32,
+    pub solved: u32,
+    pub best_points: u32,
+}
+
+// Results of every puzzle tried, kept between sessions.
+#[derive(Default)]
+pub struct PuzzleScores {
+    entries: Vec<PuzzleScore>,
+}
+
+const SCORES_HEADER: &str = "checkers-puzzle-scores 1";
+
+impl PuzzleScores {
+    // A missing file means no puzzle was tried yet.
+    pub fn load(path: &str) -> Result<Self, CheckersError> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::from_text(&text),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), CheckersError> {
+        Ok(fs::write(path, self.to_text())?)
+    }
+
+    // A header line, then one line per puzzle: FEN;attempts;solved;best points.
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![SCORES_HEADER.to_string()];
+        lines.extend(self.entries.iter().map(|e| format!("{};{};{};{}", e.fen, e.attempts, e.solved, e.best_points)));
+        lines.join("\n") + "\n"
+    }
+
+    pub fn from_text(text: &str) -> Result<Self, CheckersError> {
+        let error = || CheckersError::SerializationError("puzzle scores: invalid line".to_string());
+        let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+        if lines.next().map(str::trim) != Some(SCORES_HEADER) {
+            return Err(CheckersError::SerializationError("puzzle scores: missing header".to_string()));
+        }
+        let entries = lines.map(|line| {
+            let fields: Vec<&str> = line.trim().split(';').collect();
+            if fields.len() != 4 {
+                return Err(error());
+            }
+            let number = |i: usize| fields[i].parse::<u32>().map_err(|_| error());
+            Ok(PuzzleScore { fen: fields[0].to_string(), attempts: number(1)?, solved: number(2)?, best_points: number(3)? })
+        }).collect::<Result<Vec<PuzzleScore>, CheckersError>>()?;
+        Ok(Self { entries })
+    }
+
+    pub fn get(&self, fen: &str) -> Option<&PuzzleScore> {
+        self.entries.iter().find(|e| e.fen == fen)
+    }
+
+    // Records an attempt, with the points earned when the puzzle was solved.
+    pub fn record(&mut self, fen: &str, points: Option<u32>) {
+        let index = match self.entries.iter().position(|e| e.fen == fen) {
+            Some(index) => index,
+            None => {
+                self.entries.push(PuzzleScore { fen: fen.to_string(), attempts: 0, solved: 0, best_points: 0 });
+                self.entries.len() - 1
+            }
+        };
+        let entry = &mut self.entries[index];
+        entry.attempts += 1;
+        if let Some(points) = points {
+            entry.solved += 1;
+            entry.best_points = entry.best_points.max(points);
+        }
+    }
+
+    pub fn solved_count(&self) -> usize {
+        self.entries.iter().filter(|e| e.solved > 0).count()
+    }
+
+    pub fn total_points(&self) -> u32 {
+        self.entries.iter().map(|e| e.best_points).sum()
+    }
+}
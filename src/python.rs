@@ -0,0 +1,150 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use crate::{CheckersColor, CountEstimator, MinMaxBot, MoveExecutor};
+use crate::checkers_utils::CheckersError;
+use crate::notation::{board_from_fen, fen_from_board, move_from_pdn, move_to_pdn, square_number};
+
+impl From<CheckersError> for PyErr {
+    fn from(err: CheckersError) -> Self {
+        PyValueError::new_err(err.to_string())
+    }
+}
+
+fn parse_color(text: &str) -> PyResult<CheckersColor> {
+    match text.to_lowercase().as_str() {
+        "white" | "w" => Ok(CheckersColor::White),
+        "black" | "b" => Ok(CheckersColor::Black),
+        _ => Err(PyValueError::new_err(format!("unknown color '{}'", text))),
+    }
+}
+
+// A position: the board together with the side to move. Boards are immutable, apply returns a new one.
+#[pyclass(name = "Board", module = "checkers", frozen)]
+#[derive(Clone)]
+pub struct PyBoard {
+    board: crate::Board,
+    side_to_move: CheckersColor,
+}
+
+#[pymethods]
+impl PyBoard {
+    #[new]
+    #[pyo3(signature = (rows = 3))]
+    fn new(rows: usize) -> PyResult<Self> {
+        Ok(Self {
+            board: crate::Board::try_new(rows)?,
+            side_to_move: CheckersColor::White,
+        })
+    }
+
+    #[staticmethod]
+    fn from_fen(fen: &str) -> PyResult<Self> {
+        let (board, side_to_move) = board_from_fen(fen)?;
+        Ok(Self { board, side_to_move })
+    }
+
+    fn fen(&self) -> String {
+        fen_from_board(&self.board, self.side_to_move)
+    }
+
+    #[getter]
+    fn side_to_move(&self) -> String {
+        format!("{:?}", self.side_to_move)
+    }
+
+    fn pieces_count(&self, color: &str) -> PyResult<usize> {
+        Ok(self.board.pieces_count(parse_color(color)?))
+    }
+
+    fn legal_moves(&self) -> Vec<String> {
+        MoveExecutor::get_legal_moves(&self.board, self.side_to_move).iter().map(move_to_pdn).collect()
+    }
+
+    fn apply(&self, game_move: &str) -> PyResult<Self> {
+        let game_move = move_from_pdn(&self.board, self.side_to_move, game_move)?;
+        let board = MoveExecutor::execute_checked(&self.board, self.side_to_move, &game_move)?;
+        Ok(Self {
+            board: MoveExecutor::promote_to_queen(&board),
+            side_to_move: self.side_to_move.opposite_color(),
+        })
+    }
+
+    fn is_game_over(&self) -> bool {
+        MoveExecutor::has_game_ended(self.board, self.side_to_move)
+    }
+
+    // The winner of a finished game ("White" or "Black"), None while the game goes on.
+    fn winner(&self) -> Option<String> {
+        if self.is_game_over() {
+            Some(format!("{:?}", self.side_to_move.opposite_color()))
+        } else {
+            None
+        }
+    }
+
+    // Every legal move with its minmax score from the point of view of the side to move, best first.
+    #[pyo3(signature = (depth = 4, pawn_weight = 1, queen_weight = 3))]
+    fn analyse(&self, py: Python<'_>, depth: usize, pawn_weight: usize, queen_weight: usize) -> PyResult<Vec<(String, i32)>> {
+        if depth == 0 {
+            return Err(PyValueError::new_err("depth should be at least 1"));
+        }
+        let (board, color) = (self.board, self.side_to_move);
+        let analysis = py.detach(move || {
+            let estimator = CountEstimator::new(pawn_weight, queen_weight);
+            MinMaxBot::new("python", color, depth, &estimator).analyse(board, color)
+        });
+        Ok(analysis.moves.iter().map(|m| (move_to_pdn(&m.game_move), m.score)).collect())
+    }
+
+    #[pyo3(signature = (depth = 4, pawn_weight = 1, queen_weight = 3))]
+    fn best_move(&self, py: Python<'_>, depth: usize, pawn_weight: usize, queen_weight: usize) -> PyResult<Option<String>> {
+        Ok(self.analyse(py, depth, pawn_weight, queen_weight)?.into_iter().next().map(|(m, _)| m))
+    }
+
+    fn perft(&self, depth: usize) -> u64 {
+        MoveExecutor::perft(&self.board, self.side_to_move, depth)
+    }
+
+    fn encode(&self) -> Vec<i8> {
+        self.board.to_array().to_vec()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Board('{}')", self.fen())
+    }
+}
+
+// Encodes boards as a row-major int8 matrix of shape (len(boards), 32), see Board::to_array.
+// numpy.frombuffer(data, dtype=numpy.int8).reshape(-1, 32) turns it into an array without copying.
+#[pyfunction]
+fn encode_batch<'py>(py: Python<'py>, boards: Vec<PyRef<'py, PyBoard>>) -> Bound<'py, PyBytes> {
+    let data: Vec<u8> = boards.iter()
+        .flat_map(|b| b.board.to_array())
+        .map(|value| value as u8)
+        .collect();
+    PyBytes::new(py, &data)
+}
+
+// Legal move masks for a batch: one row of 32 * 32 flags per board, set for every (start, end) square pair of a legal move.
+#[pyfunction]
+fn legal_move_masks<'py>(py: Python<'py>, boards: Vec<PyRef<'py, PyBoard>>) -> Bound<'py, PyBytes> {
+    let mut data = vec![0_u8; boards.len() * 32 * 32];
+    for (i, b) in boards.iter().enumerate() {
+        for game_move in MoveExecutor::get_legal_moves(&b.board, b.side_to_move) {
+            let (start, end) = (game_move.start_pair(), game_move.end_pair());
+            let start = square_number(start.0, start.1).unwrap() - 1;
+            let end = square_number(end.0, end.1).unwrap() - 1;
+            data[i * 32 * 32 + start * 32 + end] = 1;
+        }
+    }
+    PyBytes::new(py, &data)
+}
+
+#[pymodule]
+fn checkers(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyBoard>()?;
+    m.add_function(wrap_pyfunction!(encode_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(legal_move_masks, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use crate::{Board, CheckersColor, GameMove, GameState, MoveExecutor};
+use crate::checkers_game::Game;
+use crate::checkers_utils::{alias_from_coordinates, CheckersError};
+use crate::notation::{move_to_pdn, square_number, PdnGame};
+
+// Steps through a recorded game: the position after every ply is kept, so moving back and
+// jumping do not have to replay the moves.
+pub struct Replay {
+    positions: Vec<Board>,
+    moves: Vec<GameMove>,
+    first_color: CheckersColor,
+    ply: usize,
+    pub flipped: bool,
+}
+
+impl Replay {
+    // Checks every move against the rules, a game with an illegal move is rejected.
+    pub fn from_moves(board: Board, side_to_move: CheckersColor, moves: &[GameMove]) -> Result<Self, CheckersError> {
+        let mut positions = vec![board];
+        let mut color = side_to_move;
+        for game_move in moves {
+            let after = MoveExecutor::execute_checked(positions.last().unwrap(), color, game_move)?;
+            positions.push(MoveExecutor::promote_to_queen(&after));
+            color = color.opposite_color();
+        }
+        Ok(Self { positions, moves: moves.to_vec(), first_color: side_to_move, ply: 0, flipped: false })
+    }
+
+    pub fn from_pdn(pdn: &PdnGame) -> Result<Self, CheckersError> {
+        let (board, color) = pdn.start_position()?;
+        Self::from_moves(board, color, &pdn.game_moves()?)
+    }
+
+    // The moves played so far in a game, replayed from the position before the first of them.
+    pub fn from_state(state: &GameState) -> Result<Self, CheckersError> {
+        let moves = state.history();
+        let start = state.snapshots().first().map_or(state.board(), |(board, _, _)| *board);
+        let first_color = if moves.len().is_multiple_of(2) { state.side_to_move() } else { state.side_to_move().opposite_color() };
+        Self::from_moves(start, first_color, moves)
+    }
+
+    pub fn from_game(game: &Game) -> Result<Self, CheckersError> {
+        Self::from_state(game.state())
+    }
+
+    // Number of plies of the game.
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    // Number of plies played in the current position.
+    pub fn ply(&self) -> usize {
+        self.ply
+    }
+
+    pub fn board(&self) -> Board {
+        self.positions[self.ply]
+    }
+
+    pub fn side_to_move(&self) -> CheckersColor {
+        if self.ply.is_multiple_of(2) { self.first_color } else { self.first_color.opposite_color() }
+    }
+
+    // The move that led to the current position.
+    pub fn last_move(&self) -> Option<&GameMove> {
+        self.ply.checked_sub(1).map(|ply| &self.moves[ply])
+    }
+
+    // Squares of the pieces taken by the last move.
+    pub fn captured(&self) -> Vec<(usize, usize)> {
+        self.last_move().map(GameMove::captured).unwrap_or_default()
+    }
+
+    pub fn forward(&mut self) -> bool {
+        self.jump(self.ply + 1)
+    }
+
+    pub fn back(&mut self) -> bool {
+        self.ply > 0 && self.jump(self.ply - 1)
+    }
+
+    // Returns false and stays on the current ply when the game is shorter.
+    pub fn jump(&mut self, ply: usize) -> bool {
+        if ply > self.len() {
+            return false;
+        }
+        self.ply = ply;
+        true
+    }
+
+    pub fn first(&mut self) {
+        self.ply = 0;
+    }
+
+    pub fn last(&mut self) {
+        self.ply = self.len();
+    }
+
+    pub fn flip(&mut self) {
+        self.flipped = !self.flipped;
+    }
+
+    // The last move with its number, e.g. "12... 15x24x31, captured 19 (E5), 27 (F3)".
+    pub fn move_text(&self) -> Option<String> {
+        let game_move = self.last_move()?;
+        let ply = self.ply - 1 + if self.first_color == CheckersColor::White { 0 } else { 1 };
+        let mut text = format!("{}{} {}", ply / 2 + 1, if ply.is_multiple_of(2) { "." } else { "..." }, move_to_pdn(game_move));
+        let captured: Vec<String> = self.captured().iter()
+            .map(|&(x, y)| format!("{} ({})", square_number(x, y).unwrap(), alias_from_coordinates(x, y).unwrap()))
+            .collect();
+        if !captured.is_empty() {
+            text += &format!(", captured {}", captured.join(", "));
+        }
+        Some(text)
+    }
+
+    pub fn status(&self) -> String {
+        let text = self.move_text().unwrap_or_else(|| "Start position".to_string());
+        format!("Ply {}/{}: {}, {:?} to move", self.ply, self.len(), text, self.side_to_move())
+    }
+
+    pub fn render(&self) -> String {
+        format!("{}{}", self.board().repr_oriented(self.flipped), self.status())
+    }
+}
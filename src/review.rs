@@ -0,0 +1,231 @@
+use crate::{Board, CheckersColor, GameMove, MoveExecutor};
+use crate::analysis::{self, format_score};
+use crate::board_estimators::Estimator;
+use crate::checkers_utils::CheckersError;
+use crate::notation::{fen_from_board, move_to_pdn};
+
+// Scores of won and lost positions are cut to this many pawns when computing losses.
+const WIN_SCORE: i32 = 100;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MoveQuality {
+    Good,
+    Inaccuracy,
+    Mistake,
+    Blunder,
+}
+
+impl MoveQuality {
+    // PDN move strength annotation.
+    pub fn glyph(&self) -> &'static str {
+        match self {
+            MoveQuality::Good => "",
+            MoveQuality::Inaccuracy => "?!",
+            MoveQuality::Mistake => "?",
+            MoveQuality::Blunder => "??",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MoveQuality::Good => "good",
+            MoveQuality::Inaccuracy => "inaccuracy",
+            MoveQuality::Mistake => "mistake",
+            MoveQuality::Blunder => "blunder",
+        }
+    }
+}
+
+// Smallest score losses, in pawns, of each kind of bad move. review_game scales them by the pawn value
+// of its estimator.
+#[derive(Copy, Clone, Debug)]
+pub struct ReviewThresholds {
+    pub inaccuracy: i32,
+    pub mistake: i32,
+    pub blunder: i32,
+}
+
+impl Default for ReviewThresholds {
+    fn default() -> Self {
+        Self {
+            inaccuracy: 1,
+            mistake: 2,
+            blunder: 3,
+        }
+    }
+}
+
+impl ReviewThresholds {
+    // The thresholds in the units of an estimator with the given pawn value.
+    pub fn scaled(&self, pawn_value: i32) -> Self {
+        Self {
+            inaccuracy: self.inaccuracy.saturating_mul(pawn_value),
+            mistake: self.mistake.saturating_mul(pawn_value),
+            blunder: self.blunder.saturating_mul(pawn_value),
+        }
+    }
+
+    pub fn quality(&self, loss: i32) -> MoveQuality {
+        if loss >= self.blunder {
+            MoveQuality::Blunder
+        } else if loss >= self.mistake {
+            MoveQuality::Mistake
+        } else if loss >= self.inaccuracy {
+            MoveQuality::Inaccuracy
+        } else {
+            MoveQuality::Good
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MoveReview {
+    pub color: CheckersColor,
+    pub game_move: GameMove,
+    // Scores and the loss are from the point of view of the player who moved, in the units of the estimator.
+    pub score: i32,
+    pub best_move: GameMove,
+    pub best_score: i32,
+    // The best move followed by the expected answers.
+    pub best_line: Vec<GameMove>,
+    pub loss: i32,
+    pub quality: MoveQuality,
+}
+
+pub struct GameReview {
+    pub board: Board,
+    pub side_to_move: CheckersColor,
+    pub moves: Vec<MoveReview>,
+    pub depth: usize,
+    pub pawn_value: i32,
+}
+
+// Searches every position of the game and compares the played move with the best one. A move that
+// is not legal in its position is an IllegalMove error.
+pub fn review_game(board: Board, side_to_move: CheckersColor, moves: &[GameMove], depth: usize,
+                   estimator: &dyn Estimator, thresholds: &ReviewThresholds) -> Result<GameReview, CheckersError> {
+    let pawn_value = estimator.pawn_value();
+    let thresholds = thresholds.scaled(pawn_value);
+    let win_score = WIN_SCORE.saturating_mul(pawn_value);
+    let clamp = |score: i32| score.clamp(-win_score, win_score);
+    let mut reviews = Vec::new();
+    let (mut current, mut color) = (board, side_to_move);
+    for game_move in moves {
+        let analysis = analysis::analyse(current, color, depth, estimator);
+        let illegal = || CheckersError::IllegalMove(move_to_pdn(game_move));
+        let best = analysis.best().ok_or_else(illegal)?;
+        let played = analysis.moves.iter().find(|m| m.game_move == *game_move).ok_or_else(illegal)?;
+        let loss = (clamp(best.score) - clamp(played.score)).max(0);
+        let mut best_line = vec![best.game_move.clone()];
+        best_line.extend(best.line.iter().cloned());
+        reviews.push(MoveReview {
+            color,
+            game_move: game_move.clone(),
+            score: played.score,
+            best_move: best.game_move.clone(),
+            best_score: best.score,
+            best_line,
+            loss,
+            quality: thresholds.quality(loss),
+        });
+        current = MoveExecutor::promote_to_queen(&MoveExecutor::execute(&current, game_move));
+        color = color.opposite_color();
+    }
+    Ok(GameReview { board, side_to_move, moves: reviews, depth, pawn_value })
+}
+
+// Move text with numbers, starting at the given ply counted from the first White move.
+fn numbered(first_ply: usize, moves: &[GameMove]) -> String {
+    let mut tokens = Vec::new();
+    for (ply, game_move) in (first_ply..).zip(moves) {
+        if ply.is_multiple_of(2) {
+            tokens.push(format!("{}.", ply / 2 + 1));
+        } else if ply == first_ply {
+            tokens.push(format!("{}...", ply / 2 + 1));
+        }
+        tokens.push(move_to_pdn(game_move));
+    }
+    tokens.join(" ")
+}
+
+impl GameReview {
+    // In pawns.
+    pub fn average_loss(&self, color: CheckersColor) -> f64 {
+        let losses: Vec<i32> = self.moves.iter().filter(|m| m.color == color).map(|m| m.loss).collect();
+        if losses.is_empty() {
+            return 0.0;
+        }
+        losses.iter().sum::<i32>() as f64 / losses.len() as f64 / self.pawn_value as f64
+    }
+
+    pub fn count(&self, color: CheckersColor, quality: MoveQuality) -> usize {
+        self.moves.iter().filter(|m| m.color == color && m.quality == quality).count()
+    }
+
+    pub fn summary(&self) -> String {
+        let mut lines = Vec::new();
+        for color in [CheckersColor::White, CheckersColor::Black] {
+            let moves = self.moves.iter().filter(|m| m.color == color).count();
+            lines.push(format!("{:?}: {} moves, average loss {:.2}, {} inaccuracies, {} mistakes, {} blunders",
+                               color, moves, self.average_loss(color), self.count(color, MoveQuality::Inaccuracy),
+                               self.count(color, MoveQuality::Mistake), self.count(color, MoveQuality::Blunder)));
+        }
+        for (ply, review) in (self.first_ply()..).zip(&self.moves) {
+            if review.quality != MoveQuality::Good {
+                lines.push(format!("{}{} {}{} {}: {}, best {} {}",
+                                   ply / 2 + 1, if ply.is_multiple_of(2) { "." } else { "..." },
+                                   move_to_pdn(&review.game_move), review.quality.glyph(), review.quality.name(),
+                                   format_score(review.score), move_to_pdn(&review.best_move), format_score(review.best_score)));
+            }
+        }
+        lines.join("\n")
+    }
+
+    fn first_ply(&self) -> usize {
+        match self.side_to_move {
+            CheckersColor::White => 0,
+            CheckersColor::Black => 1,
+        }
+    }
+
+    // An annotated PDN game with the given tags; the start position is added as a FEN tag unless
+    // the game starts from the initial position.
+    pub fn to_pdn(&self, tags: &[(String, String)], result: &str) -> String {
+        let mut text = String::new();
+        for (name, value) in tags.iter().filter(|(name, _)| name != "FEN" && name != "Result") {
+            text += &format!("[{} \"{}\"]\n", name, value);
+        }
+        let initial = self.side_to_move == CheckersColor::White && self.board.get_board() == Board::new(3).get_board();
+        if !initial {
+            text += &format!("[FEN \"{}\"]\n", self.start_fen());
+        }
+        text += &format!("[Result \"{}\"]\n[Annotator \"checkers depth {}\"]\n", result, self.depth);
+        format!("{}\n{} {}\n", text, self.annotated_moves(), result)
+    }
+
+    // Move text of an annotated PDN game: every move gets its score as a comment, bad moves
+    // get a glyph, a comment with the loss and the best line as a variation.
+    pub fn annotated_moves(&self) -> String {
+        let mut tokens = Vec::new();
+        for (ply, review) in (self.first_ply()..).zip(&self.moves) {
+            if ply.is_multiple_of(2) {
+                tokens.push(format!("{}.", ply / 2 + 1));
+            } else {
+                tokens.push(format!("{}...", ply / 2 + 1));
+            }
+            tokens.push(format!("{}{}", move_to_pdn(&review.game_move), review.quality.glyph()));
+            if review.quality == MoveQuality::Good {
+                tokens.push(format!("{{{}}}", format_score(review.score)));
+            } else {
+                tokens.push(format!("{{{} {}, best {} {}}}", format_score(review.score), review.quality.name(),
+                                    move_to_pdn(&review.best_move), format_score(review.best_score)));
+                tokens.push(format!("({})", numbered(ply, &review.best_line)));
+            }
+        }
+        tokens.join(" ")
+    }
+
+    pub fn start_fen(&self) -> String {
+        fen_from_board(&self.board, self.side_to_move)
+    }
+}
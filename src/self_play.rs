@@ -0,0 +1,200 @@
+use std::collections::VecDeque;
+use std::fs;
+use rand::Rng;
+use crate::{Board, CheckersColor, GameResult, GameState, GameStatus};
+use crate::checkers_utils::CheckersError;
+use crate::mcts::{policy_index, Mcts, PolicyValueNet, POLICY_SIZE};
+use crate::notation::{board_from_fen, fen_from_board};
+
+const HEADER: &str = "checkers-replay 1";
+
+// A position from a self-play game with the training targets of the policy/value network.
+#[derive(Clone)]
+pub struct PolicySample {
+    pub board: Board,
+    pub side_to_move: CheckersColor,
+    // Visit distribution of the search over the policy outputs of every legal move, see mcts::policy_index.
+    pub policy: Vec<(usize, f32)>,
+    // Final result of the game for the side to move: 1 for a win, -1 for a loss, 0 for a draw.
+    pub value: f32,
+}
+
+fn format_error(message: &str) -> CheckersError {
+    CheckersError::SerializationError(format!("replay buffer: {}", message))
+}
+
+impl PolicySample {
+    // One line: the position as FEN, the value and the policy as index:probability pairs, separated by semicolons.
+    fn to_line(&self) -> String {
+        let policy: Vec<String> = self.policy.iter().map(|(i, p)| format!("{}:{}", i, p)).collect();
+        format!("{};{};{}", fen_from_board(&self.board, self.side_to_move), self.value, policy.join(" "))
+    }
+
+    fn from_line(line: &str) -> Result<Self, CheckersError> {
+        let parts: Vec<&str> = line.split(';').collect();
+        if parts.len() != 3 {
+            return Err(format_error("expected a position, a value and a policy"));
+        }
+        let (board, side_to_move) = board_from_fen(parts[0].trim())?;
+        let value = parts[1].trim().parse::<f32>().map_err(|_| format_error("invalid value"))?;
+        let policy = parts[2].split_whitespace().map(|entry| {
+            let (index, probability) = entry.split_once(':').ok_or_else(|| format_error("invalid policy entry"))?;
+            let index = index.parse::<usize>().ok().filter(|&i| i < POLICY_SIZE);
+            let probability = probability.parse::<f32>().ok();
+            index.zip(probability).ok_or_else(|| format_error("invalid policy entry"))
+        }).collect::<Result<Vec<(usize, f32)>, CheckersError>>()?;
+        if policy.is_empty() {
+            return Err(format_error("empty policy"));
+        }
+        Ok(Self { board, side_to_move, policy, value })
+    }
+}
+
+// The most recent self-play positions, the oldest are dropped once the capacity is reached.
+pub struct ReplayBuffer {
+    samples: VecDeque<PolicySample>,
+    capacity: usize,
+}
+
+impl ReplayBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity.min(1 << 16)),
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn push(&mut self, sample: PolicySample) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn extend<I: IntoIterator<Item = PolicySample>>(&mut self, samples: I) {
+        for sample in samples {
+            self.push(sample);
+        }
+    }
+
+    pub fn sample_batch<R: Rng>(&self, size: usize, rng: &mut R) -> Vec<&PolicySample> {
+        if self.samples.is_empty() {
+            return Vec::new();
+        }
+        (0..size).map(|_| &self.samples[rng.gen_range(0..self.samples.len())]).collect()
+    }
+
+    pub fn load(path: &str, capacity: usize) -> Result<Self, CheckersError> {
+        Self::from_text(&fs::read_to_string(path)?, capacity)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), CheckersError> {
+        Ok(fs::write(path, self.to_text())?)
+    }
+
+    // A header line followed by one sample per line, oldest first.
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![HEADER.to_string()];
+        lines.extend(self.samples.iter().map(PolicySample::to_line));
+        lines.join("\n") + "\n"
+    }
+
+    pub fn from_text(text: &str, capacity: usize) -> Result<Self, CheckersError> {
+        let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+        if lines.next().map(str::trim) != Some(HEADER) {
+            return Err(format_error("missing header"));
+        }
+        let mut buffer = Self::new(capacity);
+        for line in lines {
+            buffer.push(PolicySample::from_line(line)?);
+        }
+        Ok(buffer)
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct SelfPlayConfig {
+    pub pawn_rows: usize,
+    pub simulations: usize,
+    // Moves are sampled in proportion to the visit counts for this many plies, then the most visited move is played.
+    pub temperature_plies: usize,
+    // Longer games are stopped and count as draws.
+    pub max_plies: usize,
+}
+
+impl Default for SelfPlayConfig {
+    fn default() -> Self {
+        Self {
+            pawn_rows: 3,
+            simulations: 100,
+            temperature_plies: 8,
+            max_plies: 200,
+        }
+    }
+}
+
+// Plays one game of the network against itself and returns its positions labelled with the search policy and the result.
+pub fn self_play_game<R: Rng>(net: &PolicyValueNet, config: &SelfPlayConfig, rng: &mut R) -> Vec<PolicySample> {
+    let mcts = Mcts::new(net, config.simulations);
+    let mut state = GameState::new(config.pawn_rows);
+    let mut samples = Vec::new();
+    while state.status() == GameStatus::InProgress && state.history().len() < config.max_plies {
+        let color = state.side_to_move();
+        let visits = mcts.search(state.board(), color);
+        let total: u32 = visits.iter().map(|(_, v)| v).sum();
+        let mut policy: Vec<(usize, f32)> = Vec::new();
+        for (game_move, count) in &visits {
+            let index = policy_index(game_move, color);
+            let share = *count as f32 / total.max(1) as f32;
+            match policy.iter_mut().find(|(i, _)| *i == index) {
+                Some(entry) => entry.1 += share,
+                None => policy.push((index, share)),
+            }
+        }
+        samples.push(PolicySample { board: state.board(), side_to_move: color, policy, value: 0.0 });
+
+        let chosen = if state.history().len() < config.temperature_plies && total > 0 {
+            let mut pick = rng.gen_range(0..total);
+            visits.iter().position(|(_, count)| {
+                if pick < *count {
+                    true
+                } else {
+                    pick -= count;
+                    false
+                }
+            }).unwrap()
+        } else {
+            (0..visits.len()).max_by_key(|&i| visits[i].1).unwrap()
+        };
+        state.apply(&visits[chosen].0).unwrap();
+    }
+    if let GameStatus::Finished(GameResult::Win(winner)) = state.status() {
+        for sample in samples.iter_mut() {
+            sample.value = if sample.side_to_move == winner { 1.0 } else { -1.0 };
+        }
+    }
+    samples
+}
+
+// Stochastic gradient descent on random batches from the buffer, returns the mean value and policy losses.
+pub fn train<R: Rng>(net: &mut PolicyValueNet, buffer: &ReplayBuffer, steps: usize, batch_size: usize, learning_rate: f32, rng: &mut R) -> (f32, f32) {
+    let (mut value_loss, mut policy_loss, mut count) = (0.0, 0.0, 0);
+    for _ in 0..steps {
+        for sample in buffer.sample_batch(batch_size, rng) {
+            let (value, policy) = net.train_sample(sample, learning_rate);
+            value_loss += value;
+            policy_loss += policy;
+            count += 1;
+        }
+    }
+    let count = count.max(1) as f32;
+    (value_loss / count, policy_loss / count)
+}
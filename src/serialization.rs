@@ -0,0 +1,98 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error;
+use crate::{Board, CheckersColor, Piece};
+use crate::checkers_game::SavedGame;
+use crate::checkers_utils::CheckersError;
+use crate::notation::{coordinates_from_square, square_number};
+
+// Human readable formats (JSON) get lists of square numbers per piece kind,
+// binary formats get the raw u128 board.
+
+#[derive(Serialize, Deserialize, Default)]
+struct BoardPieces {
+    white_pawns: Vec<usize>,
+    white_queens: Vec<usize>,
+    black_pawns: Vec<usize>,
+    black_queens: Vec<usize>,
+}
+
+impl From<&Board> for BoardPieces {
+    fn from(board: &Board) -> Self {
+        let mut pieces = BoardPieces::default();
+        for x in 0..8 {
+            for y in (0..8).filter(|y| (x + y) % 2 == 1) {
+                let square = square_number(x, y).unwrap();
+                match board.get_at(x, y) {
+                    Ok(Some(Piece::Pawn(CheckersColor::White))) => pieces.white_pawns.push(square),
+                    Ok(Some(Piece::Queen(CheckersColor::White))) => pieces.white_queens.push(square),
+                    Ok(Some(Piece::Pawn(CheckersColor::Black))) => pieces.black_pawns.push(square),
+                    Ok(Some(Piece::Queen(CheckersColor::Black))) => pieces.black_queens.push(square),
+                    _ => {}
+                }
+            }
+        }
+        pieces
+    }
+}
+
+impl BoardPieces {
+    fn to_board(&self) -> Result<Board, CheckersError> {
+        let mut board = Board::empty();
+        let lists = [
+            (&self.white_pawns, Board::WHITE_PAWN),
+            (&self.white_queens, Board::WHITE_QUEEN),
+            (&self.black_pawns, Board::BLACK_PAWN),
+            (&self.black_queens, Board::BLACK_QUEEN),
+        ];
+        for (squares, piece) in lists {
+            for &square in squares {
+                let (x, y) = coordinates_from_square(square)?;
+                if !board.is_empty_at(x, y)? {
+                    return Err(CheckersError::SerializationError(format!("square {} is occupied twice", square)));
+                }
+                board.set_at(x, y, piece)?;
+            }
+        }
+        Ok(board)
+    }
+}
+
+impl Serialize for Board {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            BoardPieces::from(self).serialize(serializer)
+        } else {
+            serializer.serialize_u128(self.get_board())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Board {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            BoardPieces::deserialize(deserializer)?.to_board()
+                .map_err(|err| D::Error::custom(format!("invalid board: {}", err)))
+        } else {
+            Board::from_bits(u128::deserialize(deserializer)?)
+                .map_err(|err| D::Error::custom(format!("invalid board: {}", err)))
+        }
+    }
+}
+
+impl SavedGame {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, CheckersError> {
+        Ok(serde_json::from_str(text)?)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        bincode::serialize(self).unwrap()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CheckersError> {
+        Ok(bincode::deserialize(bytes)?)
+    }
+}
@@ -1,6 +1,6 @@
-use std::cmp::max;
+use std::time::Duration;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Default)]
 pub struct NodeCounter {
     pub nodes: usize
 }
@@ -17,10 +17,101 @@ impl NodeCounter {
     }
 
     pub fn down(&mut self) {
-        self.nodes -= max(0, self.nodes - 1);
+        self.nodes = self.nodes.saturating_sub(1);
     }
 
     pub fn zero(&mut self) {
         self.nodes = 0;
     }
-}
\ No newline at end of file
+}
+
+// Counters of one search. With iterative deepening they add up all iterations, including one
+// stopped by the time limit. There are no transposition table hits or cutoffs to count: the search
+// scores every move exactly, for the analysis of all moves, so it has neither a transposition table
+// nor alpha-beta pruning. The report and the JSON mark them as not applicable.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SearchStats {
+    pub nodes: usize,
+    pub leaf_evaluations: usize,
+    // Positions whose moves were searched and the number of those moves.
+    pub expanded_nodes: usize,
+    pub children: usize,
+    // Deepest completed iteration.
+    pub depth_reached: usize,
+    // Time of every completed iteration, by depth.
+    pub depth_times: Vec<(usize, Duration)>,
+    pub elapsed: Duration,
+}
+
+impl SearchStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Average number of moves of the searched positions.
+    pub fn branching_factor(&self) -> f64 {
+        if self.expanded_nodes == 0 {
+            return 0.0;
+        }
+        self.children as f64 / self.expanded_nodes as f64
+    }
+
+    pub fn nodes_per_second(&self) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds == 0.0 {
+            return 0.0;
+        }
+        self.nodes as f64 / seconds
+    }
+
+    // Adds the counters of another search, the depths and times are left alone.
+    pub fn add(&mut self, other: &SearchStats) {
+        self.nodes += other.nodes;
+        self.leaf_evaluations += other.leaf_evaluations;
+        self.expanded_nodes += other.expanded_nodes;
+        self.children += other.children;
+    }
+
+    pub fn complete_depth(&mut self, depth: usize, time: Duration) {
+        self.depth_reached = depth;
+        self.depth_times.push((depth, time));
+    }
+
+    pub fn report(&self) -> String {
+        let mut lines = vec![
+            format!("Nodes:            {} ({:.0} per second)", self.nodes, self.nodes_per_second()),
+            format!("Leaf evaluations: {}", self.leaf_evaluations),
+            "TT hits:          n/a (no transposition table)".to_string(),
+            "Cutoffs:          n/a (no alpha-beta pruning)".to_string(),
+            format!("Branching factor: {:.2}", self.branching_factor()),
+            format!("Depth reached:    {}", self.depth_reached),
+        ];
+        for (depth, time) in &self.depth_times {
+            lines.push(format!("  depth {:>2}:       {:?}", depth, time));
+        }
+        lines.push(format!("Time:             {:?}", self.elapsed));
+        lines.join("\n")
+    }
+
+    // Times are in milliseconds.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        let depth_times: Vec<serde_json::Value> = self.depth_times.iter()
+            .map(|(depth, time)| serde_json::json!({ "depth": depth, "time_ms": time.as_secs_f64() * 1000.0 }))
+            .collect();
+        let value = serde_json::json!({
+            "nodes": self.nodes,
+            "leaf_evaluations": self.leaf_evaluations,
+            "tt_hits": null,
+            "cutoffs": null,
+            "expanded_nodes": self.expanded_nodes,
+            "children": self.children,
+            "branching_factor": self.branching_factor(),
+            "depth_reached": self.depth_reached,
+            "depth_times": depth_times,
+            "time_ms": self.elapsed.as_secs_f64() * 1000.0,
+            "nodes_per_second": self.nodes_per_second(),
+        });
+        serde_json::to_string_pretty(&value).unwrap()
+    }
+}
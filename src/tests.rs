@@ -4,7 +4,14 @@ mod tests {
     use itertools::Itertools;
     use crate::{Board, CheckersColor, MoveExecutor, Piece};
     use crate::checkers_utils::CheckersError;
-    use crate::moves::{Jump, Move, SimpleMove};
+    use crate::moves::{GameMove, Jump, Move, SimpleMove};
+    use crate::notation;
+    use crate::analysis;
+    use crate::board_estimators::CountEstimator;
+    use crate::notation::PdnGame;
+    #[cfg(feature = "terminal")]
+    use crate::players::Human;
+    use crate::diagram::Diagram;
 
     #[test]
     fn set_element_test() {
@@ -22,9 +29,9 @@ mod tests {
         let res3 = board.set_at(0, 0, Board::WHITE_QUEEN);
         let res4 = board.set_at(10, 1, Board::BLACK_PAWN);
         let res5 = board.set_at(0, 1, 0b1111);
-        assert_eq!(res3, Err(CheckersError::RuleError));
-        assert_eq!(res4, Err(CheckersError::IndexOutOfBounds));
-        assert_eq!(res5, Err(CheckersError::PawnBinaryValueError));
+        assert_eq!(res3, Err(CheckersError::LightSquare { x: 0, y: 0 }));
+        assert_eq!(res4, Err(CheckersError::IndexOutOfBounds { x: 10, y: 1 }));
+        assert_eq!(res5, Err(CheckersError::PawnBinaryValueError(0b1111)));
     }
 
     #[test]
@@ -50,7 +57,7 @@ mod tests {
 
         let elem = board.get_at(0, 10);
         let res = match elem {
-            Err(CheckersError::IndexOutOfBounds) => true,
+            Err(CheckersError::IndexOutOfBounds { x: 0, y: 10 }) => true,
             _ => false,
         };
         assert!(res);
@@ -93,7 +100,7 @@ mod tests {
         assert_eq!(board.get_board(), 0b1000_1101);
 
         let res = board.set_field_excluded(10, 0);
-        assert_eq!(res, Err(CheckersError::IndexOutOfBounds));
+        assert_eq!(res, Err(CheckersError::IndexOutOfBounds { x: 10, y: 0 }));
 
         let mut board = Board::empty();
         let _ = board.set_field_excluded(7, 6);
@@ -112,7 +119,7 @@ mod tests {
 
         let _ = board.set_field_excluded(0, 1);
         let res = board.is_field_excluded(10, 0);
-        assert_eq!(res, Err(CheckersError::IndexOutOfBounds));
+        assert_eq!(res, Err(CheckersError::IndexOutOfBounds { x: 10, y: 0 }));
     }
 
     #[test]
@@ -129,8 +136,8 @@ mod tests {
         let board = Board::new(2);
         assert!(!board.is_empty_at(0, 1).unwrap());
         assert!(board.is_empty_at(2, 3).unwrap());
-        assert_eq!(board.is_empty_at(10, 0), Err(CheckersError::IndexOutOfBounds));
-        assert_eq!(board.is_empty_at(2, 4), Err(CheckersError::RuleError));
+        assert_eq!(board.is_empty_at(10, 0), Err(CheckersError::IndexOutOfBounds { x: 10, y: 0 }));
+        assert_eq!(board.is_empty_at(2, 4), Err(CheckersError::LightSquare { x: 2, y: 4 }));
     }
 
     #[test]
@@ -209,6 +216,23 @@ mod tests {
         assert_eq!(comp_board.get_board(), board.get_board());
     }
 
+    #[test]
+    fn pawn_capture_branches_test() {
+        let board = Board::from_mockup([
+            ["  ", "  ", "  ", "  ", "  ", "  ", "  ", "  "],
+            ["  ", "  ", "  ", "  ", "  ", "  ", "  ", "  "],
+            ["  ", "  ", "  ", "  ", "  ", "  ", "  ", "  "],
+            ["  ", "  ", "BP", "  ", "BP", "  ", "  ", "  "],
+            ["  ", "  ", "  ", "WP", "  ", "  ", "  ", "  "],
+            ["  ", "  ", "  ", "  ", "  ", "  ", "  ", "  "],
+            ["  ", "  ", "  ", "  ", "  ", "  ", "  ", "  "],
+            ["  ", "  ", "  ", "  ", "  ", "  ", "  ", "  "]]);
+        let captures = MoveExecutor::get_all_captures(&board, CheckersColor::White);
+        assert_eq!(captures.len(), 2);
+        assert!(captures.iter().all(|path| path.len() == 1));
+        assert_ne!(captures[0][0].end_pair(), captures[1][0].end_pair());
+    }
+
     #[test]
     fn get_all_pawn_moves_test() {
         println!("Checking basic pawns...");
@@ -272,4 +296,956 @@ mod tests {
             ["BQ", "  ", "BQ", "  ", "  ", "  ", "  ", "  "]]);
         assert_eq!(board_cmp.get_board(), board.get_board());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn count_estimator_test() {
+        use crate::board_estimators::Estimator;
+        let (board, _) = notation::board_from_fen("W:W21,22,K23:B1").unwrap();
+        let count = CountEstimator::new(1, 3);
+        assert_eq!(count.estimate(board, CheckersColor::White, false), 4);
+        assert_eq!(count.estimate(board, CheckersColor::Black, false), -4);
+    }
+
+    #[test]
+    fn square_number_test() {
+        assert_eq!(notation::square_number(0, 1), Ok(1));
+        assert_eq!(notation::square_number(7, 6), Ok(32));
+        assert_eq!(notation::square_number(0, 0), Err(CheckersError::LightSquare { x: 0, y: 0 }));
+        for square in 1..=32 {
+            let (x, y) = notation::coordinates_from_square(square).unwrap();
+            assert_eq!(notation::square_number(x, y), Ok(square));
+        }
+        assert!(matches!(notation::coordinates_from_square(33), Err(CheckersError::NotationError { .. })));
+    }
+
+    #[test]
+    fn fen_test() {
+        let (board, color) = notation::board_from_fen("W:W21-32:B1-12").unwrap();
+        assert_eq!(board.get_board(), Board::new(3).get_board());
+        assert_eq!(color, CheckersColor::White);
+
+        let (board, color) = notation::board_from_fen("B:WK1,30:B5").unwrap();
+        assert!(matches!(board.get_at(0, 1), Ok(Some(Piece::Queen(CheckersColor::White)))));
+        assert_eq!(color, CheckersColor::Black);
+        assert_eq!(notation::fen_from_board(&board, color), "B:WK1,30:B5");
+
+        assert!(notation::board_from_fen("X:W1").is_err());
+        assert!(notation::board_from_fen("W:W33").is_err());
+    }
+
+    #[test]
+    fn perft_test() {
+        let board = Board::new(3);
+        assert_eq!(MoveExecutor::perft(&board, CheckersColor::White, 1), 7);
+        assert_eq!(MoveExecutor::perft(&board, CheckersColor::White, 2), 49);
+        assert_eq!(MoveExecutor::perft(&board, CheckersColor::White, 3), 302);
+    }
+
+    #[test]
+    fn pdn_test() {
+        let text = "[GameType \"26\"]\n[FEN \"W:W18,21:B14\"]\n1. 18x9 {forced} 14-18 1-1";
+        let games = PdnGame::parse_all(text).unwrap();
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].moves, vec!["18x9".to_string(), "14-18".to_string()]);
+        assert_eq!(games[0].result, "1-1");
+        assert!(games[0].game_moves().is_err());
+
+        let (board, color) = games[0].start_position().unwrap();
+        let capture = notation::move_from_pdn(&board, color, "18x9").unwrap();
+        assert_eq!(capture.captured(), vec![notation::coordinates_from_square(14).unwrap()]);
+        assert!(matches!(notation::move_from_pdn(&board, color, "21-17"), Err(CheckersError::IllegalMove(_))));
+
+        let pdn = PdnGame::new(Some("W:W18,21:B14".to_string()), &[capture], "2-0");
+        let parsed = PdnGame::parse_all(&pdn.to_pdn()).unwrap();
+        assert_eq!(parsed[0].moves, vec!["18x9".to_string()]);
+        assert_eq!(parsed[0].game_moves().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn analysis_test() {
+        let estimator = CountEstimator::new(1, 3);
+        let (board, color) = notation::board_from_fen("W:W18,21:B14").unwrap();
+        let analysis = analysis::analyse(board, color, 3, &estimator);
+        assert_eq!(analysis.moves.len(), 1);
+        assert_eq!(notation::move_to_pdn(&analysis.principal_variation[0]), "18x9");
+        assert!(analysis.score().unwrap() > i32::MAX / 2);
+
+        let board = Board::new(2);
+        let analysis = analysis::analyse(board, CheckersColor::White, 3, &estimator);
+        assert_eq!(analysis.moves.len(), MoveExecutor::get_legal_moves(&board, CheckersColor::White).len());
+        assert!(analysis.moves.windows(2).all(|w| w[0].score >= w[1].score));
+        assert_eq!(analysis.principal_variation.len(), 3);
+        assert_eq!(analysis.leaf_nodes as u64, MoveExecutor::perft(&board, CheckersColor::White, 3));
+
+        let timed = analysis::analyse_timed(board, CheckersColor::White, 12, std::time::Duration::ZERO, &estimator);
+        assert_eq!(timed.depth, 1);
+        assert_eq!(timed.moves.len(), analysis.moves.len());
+        let timed = analysis::analyse_timed(board, CheckersColor::White, 3, std::time::Duration::from_secs(60), &estimator);
+        assert_eq!(timed.depth, 3);
+        assert_eq!(timed.leaf_nodes, analysis.leaf_nodes);
+        assert_eq!(analysis::analyse(board, CheckersColor::White, 0, &estimator).depth, 1);
+        assert_eq!(analysis::analyse_timed(board, CheckersColor::White, 0, std::time::Duration::from_secs(60), &estimator).depth, 1);
+
+        let bot = crate::MinMaxBot::new("MinMax", CheckersColor::White, 3, &estimator);
+        let legal_moves = MoveExecutor::get_legal_moves(&board, CheckersColor::White);
+        let crate::Decision::Move(index) = crate::players::decide(&bot, board, &legal_moves, false) else { panic!("no move") };
+        let chosen = analysis.moves.iter().find(|m| m.game_move == legal_moves[index]).unwrap();
+        assert_eq!(Some(chosen.score), analysis.score());
+    }
+
+    #[test]
+    fn neural_estimator_test() {
+        use rand::SeedableRng;
+        use crate::board_estimators::Estimator;
+        use crate::neural::{self, NeuralEstimator, Sample};
+        let (board, _) = notation::board_from_fen("W:W21,22,K23:B1").unwrap();
+        let count = CountEstimator::new(1, 3);
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let mut network = NeuralEstimator::new(&[16, 8], &mut rng);
+        let value = network.evaluate(&board);
+        assert!((-1.0..=1.0).contains(&value));
+        assert_eq!(network.estimate(board, CheckersColor::White, false), -network.estimate(board, CheckersColor::Black, false));
+        let (lost, _) = notation::board_from_fen("B:W21:B").unwrap();
+        assert_eq!(network.estimate(lost, CheckersColor::White, true), i32::MAX);
+
+        let copy = NeuralEstimator::from_text(&network.to_text()).unwrap();
+        assert_eq!(copy.evaluate(&board), value);
+        assert!(matches!(NeuralEstimator::from_text("checkers-neural 1\nlayers 128 1\n1 2"), Err(CheckersError::SerializationError(_))));
+        assert!(matches!(NeuralEstimator::load("/nonexistent/neural.txt"), Err(CheckersError::Io { .. })));
+
+        let samples: Vec<Sample> = ["W:W21,22,23:B1", "B:W30:B1,2,3", "W:W25,26,27,28:B4", "B:W32:B5,6,7,8"].iter()
+            .map(|fen| notation::board_from_fen(fen).unwrap().0)
+            .map(|board| Sample { board, target: if board.pieces_count(CheckersColor::White) > 1 { 1.0 } else { -1.0 } })
+            .collect();
+        let first_loss = network.train_epoch(&samples, 0.05, &mut rng);
+        let mut loss = first_loss;
+        for _ in 0..200 {
+            loss = network.train_epoch(&samples, 0.05, &mut rng);
+        }
+        assert!(loss < first_loss / 4.0);
+        assert!(samples.iter().all(|s| network.evaluate(&s.board) * s.target > 0.5));
+
+        let samples = neural::self_play(2, 1, 4, &count, &mut rng);
+        assert!(!samples.is_empty());
+        assert!(samples.iter().all(|s| [-1.0, 0.0, 1.0].contains(&s.target)));
+    }
+
+    #[test]
+    fn policy_value_mcts_test() {
+        use rand::SeedableRng;
+        use crate::{DummyBot, Game};
+        use crate::mcts::{policy_index, Mcts, MctsBot, PolicyValueNet};
+        use crate::self_play::{self, ReplayBuffer, SelfPlayConfig};
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let mut net = PolicyValueNet::new(&[32], &mut rng);
+
+        // both sides see the mirrored opening move as the same policy output
+        let board = Board::new(3);
+        let white_move = notation::move_from_pdn(&board, CheckersColor::White, "22-18").unwrap();
+        let black_move = notation::move_from_pdn(&board, CheckersColor::Black, "11-15").unwrap();
+        assert_eq!(policy_index(&white_move, CheckersColor::White), policy_index(&black_move, CheckersColor::Black));
+        let moves = MoveExecutor::get_legal_moves(&board, CheckersColor::White);
+        let (priors, value) = net.predict(&board, CheckersColor::White, &moves);
+        assert_eq!(priors.len(), 7);
+        assert!((priors.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+        assert!((-1.0..=1.0).contains(&value));
+
+        let copy = PolicyValueNet::from_text(&net.to_text()).unwrap();
+        assert_eq!(copy, net);
+        assert!(matches!(PolicyValueNet::from_text("checkers-policy-value 1\ntrunk 128\n"), Err(CheckersError::SerializationError(_))));
+
+        // 13-9 leaves the black pawn without a move
+        let (board, color) = notation::board_from_fen("W:W13,14:B5").unwrap();
+        let best = Mcts::new(&net, 100).best_move(board, color).unwrap();
+        assert_eq!(notation::move_to_pdn(&best), "13-9");
+
+        let config = SelfPlayConfig { pawn_rows: 1, simulations: 16, temperature_plies: 4, max_plies: 60 };
+        let mut buffer = ReplayBuffer::new(10000);
+        for _ in 0..4 {
+            buffer.extend(self_play::self_play_game(&net, &config, &mut rng));
+        }
+        assert!(!buffer.is_empty());
+        let copy = ReplayBuffer::from_text(&buffer.to_text(), 10000).unwrap();
+        assert_eq!(copy.to_text(), buffer.to_text());
+        for sample in buffer.sample_batch(20, &mut rng) {
+            assert!([-1.0, 0.0, 1.0].contains(&sample.value));
+            assert!((sample.policy.iter().map(|(_, p)| p).sum::<f32>() - 1.0).abs() < 1e-4);
+        }
+        let mut small = ReplayBuffer::from_text(&buffer.to_text(), 5).unwrap();
+        assert_eq!(small.len(), 5);
+        small.extend(self_play::self_play_game(&net, &config, &mut rng));
+        assert_eq!(small.len(), 5);
+        assert!(ReplayBuffer::from_text("W:W13:B5;1;0:1", 10).is_err());
+
+        let (first_value, first_policy) = self_play::train(&mut net, &buffer, 1, 64, 0.01, &mut rng);
+        let mut losses = (first_value, first_policy);
+        for _ in 0..40 {
+            losses = self_play::train(&mut net, &buffer, 1, 64, 0.01, &mut rng);
+        }
+        assert!(losses.0 + losses.1 < first_value + first_policy);
+
+        let mut white = MctsBot::new("MCTS", CheckersColor::White, net, 16);
+        let mut black = DummyBot::new("Dummy", CheckersColor::Black);
+        let mut game = Game::new_with_bots(&mut white, &mut black, 2, 1);
+        game.verbose = false;
+        game.play();
+        assert!(!game.moves().is_empty());
+    }
+
+    #[test]
+    fn training_data_export_test() {
+        use rand::SeedableRng;
+        use crate::{DummyBot, Game, GameResult};
+        use crate::training_data::{self, ExportConfig, PositionRecorder, TrainingRecord};
+        let mut white = DummyBot::new("White", CheckersColor::White);
+        let mut black = DummyBot::new("Black", CheckersColor::Black);
+        let recorder = PositionRecorder::new();
+        let mut game = Game::new_with_bots(&mut white, &mut black, 2, 2);
+        game.verbose = false;
+        game.add_observer(&recorder);
+        let result = game.play();
+        let positions = recorder.take();
+        assert_eq!(positions.len(), game.moves().len());
+        assert_eq!(positions[0].0.get_board(), Board::new(2).get_board());
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        let config = ExportConfig { depth: 2, skip_plies: 0, sample_rate: 1.0 };
+        let records = training_data::label_positions(&positions, result, &config, &CountEstimator::new(1, 3), &mut rng);
+        assert!(records.len() <= positions.len());
+        let expected = match result {
+            GameResult::Win(CheckersColor::White) => 1,
+            GameResult::Win(CheckersColor::Black) => -1,
+            GameResult::Draw => 0,
+        };
+        for record in &records {
+            assert_eq!(record.result, expected);
+            assert!(MoveExecutor::get_all_captures(&record.board, record.side_to_move).is_empty());
+        }
+        let config = ExportConfig { sample_rate: 0.0, ..config };
+        assert!(training_data::label_positions(&positions, result, &config, &CountEstimator::new(1, 3), &mut rng).is_empty());
+
+        let (board, side_to_move) = notation::board_from_fen("B:W21,22,K23:B1,10").unwrap();
+        let best_move = notation::move_from_pdn(&board, side_to_move, "10-15").unwrap();
+        let record = TrainingRecord { board, side_to_move, result: -1, score: 2, best_move };
+        let mut data = Vec::new();
+        training_data::write_csv(&mut data, std::slice::from_ref(&record)).unwrap();
+        let text = String::from_utf8(data).unwrap();
+        assert!(text.starts_with("board,side_to_move,result,score,best_move\n"));
+        let read = training_data::read_csv(&text).unwrap();
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].to_csv(), record.to_csv());
+        assert_eq!(read[0].board.get_board(), board.get_board());
+        assert!(training_data::read_csv("board,side_to_move,result,score,best_move\n0,W,2,0,22-18").is_err());
+    }
+
+    #[test]
+    fn puzzle_generator_test() {
+        use crate::puzzles::{self, Puzzle, PuzzleConfig};
+        let config = PuzzleConfig::default();
+        // Black gives up a pawn on 11 and takes two back
+        let (board, color) = notation::board_from_fen("B:W14,16,25,26,28,29:B3,4,5,8").unwrap();
+        let puzzle = puzzles::find_puzzle(board, color, &config).unwrap();
+        let solution: Vec<String> = puzzle.solution.iter().map(notation::move_to_pdn).collect();
+        assert_eq!(solution, ["8-11", "16x7", "3x10x17"]);
+        assert_eq!(puzzle.gain, 1);
+        assert!(puzzles::find_puzzle(board, color, &PuzzleConfig { min_gain: 2, ..config }).is_none());
+        assert!(puzzles::find_puzzle(Board::new(3), CheckersColor::White, &config).is_none());
+
+        let pdn = puzzle.to_pdn_game().to_pdn();
+        assert!(pdn.contains("[FEN \"B:W14,16,25,26,28,29:B3,4,5,8\"]"));
+        assert!(pdn.contains("1... 8-11 2. 16x7 3x10x17 *"));
+        let games = PdnGame::parse_all(&(pdn.clone() + "\n" + &pdn)).unwrap();
+        assert_eq!(games.len(), 2);
+        let read = Puzzle::from_pdn_game(&games[0]).unwrap();
+        assert_eq!(read.solution, puzzle.solution);
+        assert_eq!(read.gain, 1);
+        assert_eq!(read.board.get_board(), board.get_board());
+
+        let found = puzzles::find_puzzles(&[(board, color), (Board::new(3), CheckersColor::White), (board, color)], &config);
+        assert_eq!(found.len(), 1);
+
+        // lost position, the scores are near i32::MIN
+        let (board, color) = notation::board_from_fen("W:W6,20,21:BK9,K17").unwrap();
+        assert!(puzzles::find_puzzle(board, color, &PuzzleConfig { depth: 4, ..config }).is_none());
+    }
+
+    #[test]
+    fn puzzle_session_test() {
+        use crate::puzzles::{self, Puzzle, PuzzleScores, PuzzleSession, PuzzleStep};
+        let games = PdnGame::parse_all("[FEN \"B:W14,16,25,26,28,29:B3,4,5,8\"]\n[Gain \"+1\"]\n1... 8-11 2. 16x7 3x10x17 *").unwrap();
+        let puzzle = Puzzle::from_pdn_game(&games[0]).unwrap();
+        let mut session = PuzzleSession::new(&puzzle, 4);
+        assert_eq!(session.hints(), ["Move the piece on G7.", "Play 8-11."]);
+        let find = |session: &PuzzleSession, text: &str| notation::move_from_pdn(&session.board(), session.side_to_move(), text).unwrap();
+
+        let wrong = find(&session, "5-9");
+        assert_eq!(session.play(&wrong), PuzzleStep::Wrong);
+        assert_eq!(session.mistakes(), 1);
+        let key = find(&session, "8-11");
+        let reply = notation::move_from_pdn(&puzzle.board, CheckersColor::Black, "8-11")
+            .map(|m| MoveExecutor::promote_to_queen(&MoveExecutor::execute(&puzzle.board, &m)))
+            .and_then(|board| notation::move_from_pdn(&board, CheckersColor::White, "16x7"))
+            .unwrap();
+        assert_eq!(session.play(&key), PuzzleStep::Correct(reply));
+        session.restart();
+        assert_eq!(session.board().get_board(), puzzle.board.get_board());
+        assert!(matches!(session.play(&key), PuzzleStep::Correct(_)));
+        let capture = find(&session, "3x10x17");
+        assert_eq!(session.play(&capture), PuzzleStep::Solved);
+        assert_eq!(session.mistakes(), 1);
+
+        assert_eq!(puzzles::puzzle_points(0, 0), 3);
+        assert_eq!(puzzles::puzzle_points(1, 1), 1);
+        assert_eq!(puzzles::puzzle_points(2, 5), 1);
+
+        let fen = "B:W14,16,25,26,28,29:B3,4,5,8";
+        let mut scores = PuzzleScores::default();
+        scores.record(fen, None);
+        scores.record(fen, Some(2));
+        scores.record("W:W25,26,28,29:B4,5,17", None);
+        let read = PuzzleScores::from_text(&scores.to_text()).unwrap();
+        let score = read.get(fen).unwrap();
+        assert_eq!((score.attempts, score.solved, score.best_points), (2, 1, 2));
+        assert_eq!((read.solved_count(), read.total_points()), (1, 2));
+        assert_eq!(PuzzleScores::load("/nonexistent/scores.txt").unwrap().total_points(), 0);
+        assert!(PuzzleScores::from_text("checkers-puzzle-scores 1\nW:W1;x;0;0").is_err());
+    }
+
+    #[test]
+    fn game_review_test() {
+        use crate::review::{self, MoveQuality, ReviewThresholds};
+        let (board, color) = notation::board_from_fen("W:W22,30:B15").unwrap();
+        let mut moves = Vec::new();
+        let mut current = board;
+        let mut side = color;
+        for text in ["22-18", "15x22", "30-25", "22x29"] {
+            let game_move = notation::move_from_pdn(&current, side, text).unwrap();
+            current = MoveExecutor::promote_to_queen(&MoveExecutor::execute(&current, &game_move));
+            side = side.opposite_color();
+            moves.push(game_move);
+        }
+        let thresholds = ReviewThresholds::default();
+        assert_eq!(thresholds.quality(0), MoveQuality::Good);
+        assert_eq!(thresholds.quality(2), MoveQuality::Mistake);
+        let game_review = review::review_game(board, color, &moves, 4, &CountEstimator::new(1, 3), &thresholds).unwrap();
+        assert_eq!(game_review.moves.len(), 4);
+        let blunder = &game_review.moves[0];
+        assert_eq!(blunder.quality, MoveQuality::Blunder);
+        assert_eq!((blunder.score, blunder.best_score, blunder.loss), (-3, 1, 4));
+        assert_eq!(notation::move_to_pdn(&blunder.best_move), "22-17");
+        assert!(game_review.moves[1..].iter().all(|m| m.quality == MoveQuality::Good || m.color == CheckersColor::White));
+        assert_eq!(game_review.count(CheckersColor::White, MoveQuality::Blunder), 1);
+        assert_eq!(game_review.average_loss(CheckersColor::Black), 0.0);
+        assert!(game_review.summary().contains("1. 22-18?? blunder: -3, best 22-17 +1"));
+
+        let pdn = game_review.to_pdn(&[("Event".to_string(), "Test".to_string())], "0-2");
+        assert!(pdn.contains("[FEN \"W:W22,30:B15\"]"));
+        assert!(pdn.contains("1. 22-18?? {-3 blunder, best 22-17 +1} (1. 22-17"));
+        assert!(pdn.contains("1... 15x22 {win} 2. 30-25 {loss}"));
+        let games = PdnGame::parse_all(&pdn).unwrap();
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].tag("Event"), Some("Test"));
+        assert_eq!(games[0].game_moves().unwrap(), moves);
+        assert_eq!(games[0].result, "0-2");
+
+        // the thresholds stay in pawns with an estimator in other units
+        let doubled = review::review_game(board, color, &moves, 4, &CountEstimator::new(2, 6), &thresholds).unwrap();
+        assert_eq!((doubled.moves[0].loss, doubled.moves[0].quality), (8, MoveQuality::Blunder));
+        assert_eq!(doubled.moves.iter().map(|m| m.quality).collect::<Vec<_>>(),
+                   game_review.moves.iter().map(|m| m.quality).collect::<Vec<_>>());
+        assert_eq!(doubled.average_loss(CheckersColor::White), game_review.average_loss(CheckersColor::White));
+        let mut skipped = moves.clone();
+        skipped.remove(1);
+        assert!(matches!(review::review_game(board, color, &skipped, 4, &CountEstimator::new(1, 3), &thresholds),
+                         Err(CheckersError::IllegalMove(_))));
+    }
+
+    #[test]
+    fn replay_viewer_test() {
+        use crate::GameState;
+        use crate::replay::Replay;
+        let text = "[FEN \"W:W22,30:B15\"]\n\n1. 22-18 15x22 2. 30-25 22x29 0-2\n";
+        let pdn = &PdnGame::parse_all(text).unwrap()[0];
+        let mut replay = Replay::from_pdn(pdn).unwrap();
+        assert_eq!(replay.len(), 4);
+        assert!(replay.last_move().is_none());
+        assert!(replay.status().starts_with("Ply 0/4: Start position, White to move"));
+        assert!(!replay.back());
+        assert!(replay.forward());
+        assert!(replay.forward());
+        assert_eq!(replay.side_to_move(), CheckersColor::White);
+        assert_eq!(replay.captured(), vec![(4, 3)]);
+        assert_eq!(replay.move_text().unwrap(), "1... 15x22, captured 18 (D4)");
+        assert!(replay.jump(4));
+        assert!(!replay.jump(5));
+        assert!(!replay.forward());
+        assert_eq!(replay.board().get_board(), notation::board_from_fen("W:W:BK29").unwrap().0.get_board());
+        replay.first();
+        assert_eq!(replay.board().get_board(), notation::board_from_fen("W:W22,30:B15").unwrap().0.get_board());
+
+        let board = Board::new(3);
+        let flipped = board.repr_oriented(true);
+        assert_eq!(board.repr_oriented(false), board.repr());
+        assert!(flipped.starts_with("   H  G  F  E  D  C  B  A \n1 "));
+        assert!(flipped.lines().nth(1).unwrap().ends_with(" 1"));
+        assert!(flipped.lines().nth(8).unwrap().starts_with("8 "));
+
+        let mut state = GameState::new(3);
+        for _ in 0..3 {
+            let game_move = state.legal_moves()[0].clone();
+            state.apply(&game_move).unwrap();
+        }
+        let mut replay = Replay::from_state(&state).unwrap();
+        assert_eq!(replay.len(), 3);
+        assert_eq!(replay.board().get_board(), Board::new(3).get_board());
+        replay.last();
+        assert_eq!(replay.board().get_board(), state.board().get_board());
+        assert_eq!(replay.side_to_move(), CheckersColor::Black);
+
+        // a saved game is only loaded when its positions match its moves
+        let saved = |state: &GameState| crate::SavedGame {
+            pawn_rows: 3,
+            board: state.board(),
+            side_to_move: state.side_to_move(),
+            idle_white: state.idle_counters().0,
+            idle_black: state.idle_counters().1,
+            random_used: 0,
+            moves: state.history().to_vec(),
+            snapshots: state.snapshots().to_vec(),
+        };
+        let white = crate::DummyBot::new("White", CheckersColor::White);
+        let black = crate::DummyBot::new("Black", CheckersColor::Black);
+        let mut game = crate::Game::new(&white, &black, 3);
+        game.load(saved(&state)).unwrap();
+        assert_eq!(Replay::from_game(&game).unwrap().len(), 3);
+        let mut wrong_board = saved(&state);
+        wrong_board.board = Board::new(2);
+        assert!(matches!(game.load(wrong_board), Err(CheckersError::InvalidConfiguration(_))));
+        let mut wrong_snapshot = saved(&state);
+        wrong_snapshot.snapshots[1] = wrong_snapshot.snapshots[2];
+        assert!(game.load(wrong_snapshot).is_err());
+        let mut wrong_side = saved(&state);
+        wrong_side.side_to_move = CheckersColor::White;
+        assert!(game.load(wrong_side).is_err());
+        let mut wrong_rows = saved(&state);
+        wrong_rows.pawn_rows = 4;
+        assert!(game.load(wrong_rows).is_err());
+        assert_eq!(game.moves().len(), 3);
+    }
+
+    #[test]
+    fn search_statistics_test() {
+        use crate::players::{MinMaxBot, Player};
+        use crate::statistics::NodeCounter;
+        let mut counter = NodeCounter::new();
+        counter.up();
+        counter.up();
+        counter.down();
+        assert_eq!(counter.nodes, 1);
+        counter.zero();
+        counter.down();
+        assert_eq!(counter.nodes, 0);
+
+        let estimator = CountEstimator::new(1, 3);
+        let board = Board::new(2);
+        let mut bot = MinMaxBot::new("bot", CheckersColor::White, 3, &estimator);
+        bot.set_node_counter(NodeCounter::new());
+        let moves = MoveExecutor::get_all_moves(&board, CheckersColor::White);
+        bot.move_piece(&moves, board, false);
+        let analysis = analysis::analyse(board, CheckersColor::White, 3, &estimator);
+        assert_eq!(bot.node_counter().unwrap().nodes, analysis.nodes);
+        assert_eq!(bot.last_search_stats().as_ref().map(|stats| stats.nodes), Some(analysis.nodes));
+        bot.move_piece(&moves[..1], board, false);
+        assert!(bot.last_search_stats().is_none());
+
+        let stats = &analysis.stats;
+        assert_eq!((stats.nodes, stats.leaf_evaluations), (analysis.nodes, analysis.leaf_nodes));
+        assert!(stats.report().contains("Cutoffs:          n/a"));
+        assert!(stats.report().contains("TT hits:          n/a"));
+        assert_eq!(stats.depth_reached, 3);
+        assert_eq!(stats.depth_times.len(), 1);
+        assert_eq!(stats.expanded_nodes + stats.leaf_evaluations, stats.nodes + 1);
+        assert_eq!(stats.children, stats.nodes);
+        assert!(stats.branching_factor() > 1.0);
+        assert!(stats.report().contains(&format!("Leaf evaluations: {}", stats.leaf_evaluations)));
+
+        let timed = analysis::analyse_timed(board, CheckersColor::White, 3, std::time::Duration::from_secs(60), &estimator);
+        assert_eq!(timed.stats.depth_reached, 3);
+        assert_eq!(timed.stats.depth_times.iter().map(|(depth, _)| *depth).collect::<Vec<usize>>(), vec![1, 2, 3]);
+        assert!(timed.stats.nodes > timed.nodes);
+        assert_eq!(timed.stats.elapsed, timed.elapsed);
+        #[cfg(feature = "serde")]
+        {
+            let json: serde_json::Value = serde_json::from_str(&timed.stats.to_json()).unwrap();
+            assert_eq!(json["nodes"], timed.stats.nodes);
+            assert_eq!(json["depth_times"].as_array().unwrap().len(), 3);
+            assert!(json["tt_hits"].is_null() && json["cutoffs"].is_null());
+        }
+    }
+
+    #[test]
+    fn game_log_test() {
+        use log::LevelFilter;
+        use crate::{Game, MinMaxBot};
+        use crate::logging::{GameLog, LogFilter};
+        let filter = LogFilter::parse("info, checkers::players=debug,checkers::players::x=off").unwrap();
+        assert_eq!(filter.level("checkers::checkers_game"), LevelFilter::Info);
+        assert_eq!(filter.level("checkers::players"), LevelFilter::Debug);
+        assert_eq!(filter.level("checkers::players::x"), LevelFilter::Off);
+        assert_eq!(filter.level("checkers::players_more"), LevelFilter::Info);
+        assert_eq!(filter.max_level(), LevelFilter::Debug);
+        assert!(LogFilter::parse("loud").is_err());
+        assert!(LogFilter::parse("checkers=sometimes").is_err());
+
+        let estimator = CountEstimator::new(1, 3);
+        let mut white = MinMaxBot::new("White bot", CheckersColor::White, 2, &estimator);
+        let mut black = MinMaxBot::new("Black bot", CheckersColor::Black, 2, &estimator);
+        let path = std::env::temp_dir().join(format!("checkers-game-{}.log", std::process::id()));
+        let other_path = std::env::temp_dir().join(format!("checkers-other-game-{}.log", std::process::id()));
+        let mut game = Game::new_with_bots(&mut white, &mut black, 2, 2);
+        game.verbose = false;
+        game.log_file = Some(path.to_str().unwrap().to_string());
+        // A second log of the same game, as a program using the library would add one.
+        let other_log = GameLog::create(other_path.to_str().unwrap(), "Alice", "Bob", 0).unwrap();
+        game.add_observer(&other_log);
+        let result = game.play();
+        let plies = game.moves().len();
+        drop(game);
+        drop(other_log);
+        let text = std::fs::read_to_string(&path).unwrap();
+        let other_text = std::fs::read_to_string(&other_path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&other_path).unwrap();
+        assert!(text.contains("Game started: White bot (White) against Black bot (Black)"));
+        assert!(text.contains("Ply 1: White White bot played "));
+        assert!(text.contains("Black bot (Black) searched "));
+        assert!(text.contains(&format!("Game over: {} after {} plies", result, plies)));
+        assert!(other_text.contains("Game started: Alice (White) against Bob (Black)"));
+        assert!(!other_text.contains("White bot"));
+        // The library leaves the logger of the program alone.
+        assert_eq!(log::max_level(), LevelFilter::Off);
+    }
+
+    #[test]
+    #[cfg(feature = "terminal")]
+    fn tui_input_test() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+        use crate::observers::GameObserver;
+        use crate::players::Decision;
+        use crate::tui::{TuiHuman, TuiState};
+        let (board, color) = notation::board_from_fen("W:W22,27:B18,10").unwrap();
+        let moves = MoveExecutor::get_legal_moves(&board, color);
+        assert_eq!(moves.len(), 1);
+        let mut state = TuiState::new();
+        assert_eq!(state.select_square(notation::parse_square("27").unwrap(), &moves), None);
+        assert_eq!(state.message, "Capture is mandatory, select a highlighted piece.");
+        let start = notation::parse_square("22").unwrap();
+        assert_eq!(state.select_square(start, &moves), None);
+        assert_eq!(state.selected, vec![start]);
+        assert_eq!(state.select_square(notation::parse_square("17").unwrap(), &moves), None);
+        assert_eq!(state.message, "Illegal target square.");
+        assert_eq!(state.select_square(start, &moves), None);
+        assert!(state.selected.is_empty());
+        state.select_square(start, &moves);
+        assert_eq!(state.select_square(notation::parse_square("15").unwrap(), &moves), None);
+        assert_eq!(state.message, "Several captures are possible, select the next landing square.");
+        assert_eq!(state.select_square(notation::parse_square("6").unwrap(), &moves), Some(0));
+
+        state.input = "22-18".to_string();
+        assert_eq!(state.submit_input(&moves), None);
+        assert_eq!(state.message, "Illegal move.");
+        state.input = "c3".to_string();
+        assert_eq!(state.submit_input(&moves), None);
+        assert_eq!(state.message, "Type a move like C3 D4 or c3-d4.");
+        state.input = "22x15x6".to_string();
+        assert_eq!(state.submit_input(&moves), Some(0));
+        assert!(state.input.is_empty());
+
+        let tui = TuiHuman::new("Tui", CheckersColor::White);
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        for c in "22x15x6".chars() {
+            assert_eq!(tui.handle_key(&mut state, key(KeyCode::Char(c)), &moves), None);
+        }
+        assert_eq!(tui.handle_key(&mut state, key(KeyCode::Enter), &moves), Some(Decision::Move(0)));
+        assert_eq!(tui.handle_key(&mut state, key(KeyCode::Char('q')), &moves), Some(Decision::Resign));
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(tui.handle_key(&mut state, ctrl_c, &moves), Some(Decision::Resign));
+
+        let board_after = MoveExecutor::execute(&board, &moves[0]);
+        tui.on_move(color, &board, &moves[0], &board_after);
+        tui.on_move(color.opposite_color(), &board_after, &moves[0], &board_after);
+        assert_eq!(tui.state.borrow().history, vec![moves[0].to_string(); 2]);
+        tui.on_undo(&board, color);
+        assert!(tui.state.borrow().history.is_empty());
+    }
+
+    #[test]
+    fn move_path_matching_test() {
+        assert_eq!(notation::parse_square("c3"), Ok((5, 2)));
+        assert_eq!(notation::parse_square("22"), Ok((5, 2)));
+        assert!(notation::parse_square("i9").is_err());
+
+        let board = Board::from_mockup([
+            ["  ", "  ", "  ", "  ", "  ", "  ", "  ", "  "],
+            ["  ", "  ", "  ", "  ", "  ", "  ", "  ", "  "],
+            ["  ", "  ", "  ", "  ", "  ", "  ", "  ", "  "],
+            ["  ", "  ", "BP", "  ", "BP", "  ", "  ", "  "],
+            ["  ", "  ", "  ", "WP", "  ", "  ", "  ", "  "],
+            ["  ", "  ", "  ", "  ", "  ", "  ", "  ", "  "],
+            ["  ", "  ", "  ", "  ", "  ", "  ", "  ", "  "],
+            ["  ", "  ", "  ", "  ", "  ", "  ", "  ", "  "]]);
+        let moves = MoveExecutor::get_legal_moves(&board, CheckersColor::White);
+        assert_eq!(moves.len(), 2);
+        let start = notation::parse_square("d4").unwrap();
+        assert_eq!(notation::moves_through(&moves, &[start]).len(), 2);
+        let target = notation::parse_square("f6").unwrap();
+        let matching = notation::moves_matching_path(&moves, &[start, target]);
+        assert_eq!(matching.len(), 1);
+        assert_eq!(moves[matching[0]].captured(), vec![notation::parse_square("e5").unwrap()]);
+        assert!(notation::moves_matching_path(&moves, &[start]).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "terminal")]
+    fn human_move_input_test() {
+        let board = Board::new(3);
+        let moves = MoveExecutor::get_legal_moves(&board, CheckersColor::White);
+        let expected = moves.iter().position(|m| notation::move_to_pdn(m) == "22-18").unwrap();
+        assert_eq!(Human::parse_move("22-18", &moves), Ok(expected));
+        assert_eq!(Human::parse_move("c3-d4", &moves), Ok(expected));
+        assert_eq!(Human::parse_move("C3 D4", &moves), Ok(expected));
+        assert_eq!(Human::parse_move("0", &moves), Ok(0));
+        assert!(Human::parse_move("7", &moves).is_err());
+        assert!(Human::parse_move("c3-c4", &moves).is_err());
+        assert!(Human::parse_move("c3", &moves).is_err());
+        assert!(Human::parse_move("foo", &moves).is_err());
+        let twice = vec![moves[expected].clone(); 2];
+        assert_eq!(Human::parse_move("22-18", &twice), Err(CheckersError::AmbiguousMove("22-18".to_string())));
+        assert_eq!(Human::matching_moves("22-18", &twice), Ok(vec![0, 1]));
+
+        let board = Board::from_mockup([
+            ["  ", "  ", "  ", "  ", "  ", "  ", "  ", "  "],
+            ["  ", "  ", "  ", "  ", "  ", "  ", "  ", "  "],
+            ["  ", "  ", "  ", "BP", "  ", "BP", "  ", "  "],
+            ["  ", "  ", "  ", "  ", "  ", "  ", "  ", "  "],
+            ["  ", "  ", "  ", "BP", "  ", "  ", "  ", "  "],
+            ["  ", "  ", "WP", "  ", "  ", "  ", "  ", "  "],
+            ["  ", "  ", "  ", "  ", "  ", "  ", "  ", "  "],
+            ["  ", "  ", "  ", "  ", "  ", "  ", "  ", "  "]]);
+        let moves = MoveExecutor::get_legal_moves(&board, CheckersColor::White);
+        let index = Human::parse_move("c3xe5xg7", &moves).unwrap();
+        assert_eq!(moves[index].end_pair(), notation::parse_square("g7").unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "terminal")]
+    fn human_blunder_check_test() {
+        let (board, color) = notation::board_from_fen("W:W22,30:B15").unwrap();
+        let blunder = notation::move_from_pdn(&board, color, "22-18").unwrap();
+        let safe = notation::move_from_pdn(&board, color, "22-17").unwrap();
+        let mut human = Human::new("Human", color);
+        assert_eq!(human.blunder_warning(&blunder, board), None);
+        human.set_blunder_check(Some(0));
+        let warning = human.blunder_warning(&blunder, board).unwrap();
+        assert!(warning.starts_with("22-18 scores -3 while 22-17 scores +1."));
+        assert!(warning.ends_with("Refutation: 15x22 30-25 22x29"));
+        assert_eq!(human.blunder_warning(&safe, board), None);
+        human.set_blunder_check(Some(4));
+        assert_eq!(human.blunder_warning(&blunder, board), None);
+        // the threshold stays in pawns with an estimator in other units
+        let doubled = CountEstimator::new(2, 6);
+        human.set_estimator(&doubled);
+        assert_eq!(human.blunder_warning(&blunder, board), None);
+        human.set_blunder_check(Some(3));
+        assert!(human.blunder_warning(&blunder, board).unwrap().starts_with("22-18 scores -6 while 22-17 scores +2."));
+    }
+
+    #[test]
+    fn diagram_test() {
+        let (board, color) = notation::board_from_fen("W:WK21,22:B17,K9,18").unwrap();
+        let mut diagram = Diagram::new(board);
+        let svg = diagram.to_svg();
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("class=\"piece").count(), 5);
+        assert_eq!(svg.matches("white-queen").count(), 1);
+        assert_eq!(svg.matches("black-pawn").count(), 2);
+        assert_eq!(svg.matches("<text").count(), 32);
+        assert!(svg.contains(r#"class="piece white-pawn" cx="180" cy="360""#));
+
+        let moves = MoveExecutor::get_legal_moves(&board, color);
+        diagram.add_arrow(&moves[0]);
+        diagram.highlight(4, 1);
+        diagram.coordinates = false;
+        diagram.flipped = true;
+        let svg = diagram.to_svg();
+        assert_eq!(svg.matches("<polyline").count(), 1);
+        assert_eq!(svg.matches("class=\"highlight").count(), 1);
+        assert!(!svg.contains("<text"));
+        assert!(svg.contains(r#"class="piece white-pawn" cx="330" cy="150""#));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serialization_test() {
+        use crate::checkers_game::SavedGame;
+        let (board, color) = notation::board_from_fen("B:WK21,22:B17,K9,18").unwrap();
+        let moves = MoveExecutor::get_legal_moves(&board, color);
+        let saved = SavedGame {
+            pawn_rows: 3,
+            board,
+            side_to_move: color,
+            idle_white: 1,
+            idle_black: 0,
+            random_used: 0,
+            moves: moves.clone(),
+            snapshots: vec![(Board::new(3), 0, 0)],
+        };
+        let json = saved.to_json();
+        assert!(json.contains("\"white_queens\": [\n      21\n    ]"));
+        let from_json = SavedGame::from_json(&json).unwrap();
+        let from_bytes = SavedGame::from_bytes(&saved.to_bytes()).unwrap();
+        for restored in [from_json, from_bytes] {
+            assert_eq!(restored.board.get_board(), board.get_board());
+            assert_eq!(restored.side_to_move, CheckersColor::Black);
+            assert_eq!(restored.idle_white, 1);
+            assert_eq!(restored.moves.len(), moves.len());
+            assert_eq!(restored.moves[0].path(), moves[0].path());
+            assert_eq!(restored.snapshots[0].0.get_board(), Board::new(3).get_board());
+        }
+        assert!(matches!(SavedGame::from_json("{}"), Err(CheckersError::SerializationError(_))));
+        let overlapping = json.replace("\"black_pawns\": [", "\"black_pawns\": [\n      21,");
+        assert!(SavedGame::from_json(&overlapping).is_err());
+
+        let white = crate::DummyBot::new("White", CheckersColor::White);
+        let black = crate::DummyBot::new("Black", CheckersColor::Black);
+        let mut game = crate::Game::new(&white, &black, 3);
+        assert_eq!(moves.len(), 2);
+        assert!(matches!(game.load(saved), Err(CheckersError::InvalidConfiguration(_))));
+    }
+
+    #[test]
+    fn checked_execution_test() {
+        let board = Board::new(3);
+        let white = CheckersColor::White;
+        let forward = SimpleMove::new(5, 2, 4, 3).unwrap();
+        let new_board = MoveExecutor::execute_move_checked(&board, white, forward).unwrap();
+        assert_eq!(new_board.get_board(), MoveExecutor::execute_move(board, forward).get_board());
+        assert_eq!(MoveExecutor::execute_move_checked(&board, white, SimpleMove::new(4, 1, 3, 2).unwrap()).err(), Some(CheckersError::NoPiece { x: 4, y: 1 }));
+        assert_eq!(MoveExecutor::execute_move_checked(&board, white, SimpleMove::new(2, 1, 3, 2).unwrap()).err(), Some(CheckersError::WrongColor { x: 2, y: 1 }));
+        assert_eq!(MoveExecutor::execute_move_checked(&board, white, SimpleMove::new(6, 1, 5, 2).unwrap()).err(), Some(CheckersError::IllegalMove("B2-C3".to_string())));
+        assert!(matches!(MoveExecutor::execute_capture_checked(&board, white, &[]), Err(CheckersError::IllegalMove(_))));
+
+        let (board, color) = notation::board_from_fen("W:W22,28:B18").unwrap();
+        let quiet = SimpleMove::new(6, 7, 5, 6).unwrap();
+        assert_eq!(MoveExecutor::execute_move_checked(&board, color, quiet).err(), Some(CheckersError::CaptureRequired("H2-G3".to_string())));
+        let wrong_capture = [Jump::new(5, 2, 3, 0, 4, 1).unwrap()];
+        assert!(matches!(MoveExecutor::execute_capture_checked(&board, color, &wrong_capture), Err(CheckersError::IllegalMove(_))));
+        let capture = [Jump::new(5, 2, 3, 4, 4, 3).unwrap()];
+        let new_board = MoveExecutor::execute_capture_checked(&board, color, &capture).unwrap();
+        assert_eq!(new_board.pieces_count(CheckersColor::Black), 0);
+    }
+
+    #[test]
+    fn observer_test() {
+        use std::cell::RefCell;
+        use crate::checkers_game::{Game, GameResult, SavedGame};
+        use crate::observers::GameObserver;
+        use crate::players::{Decision, DummyBot, Player};
+
+        #[derive(Default)]
+        struct Recorder {
+            events: RefCell<Vec<String>>,
+        }
+
+        impl GameObserver for Recorder {
+            fn on_game_start(&self, _board: &Board, side_to_move: CheckersColor) {
+                self.events.borrow_mut().push(format!("start {:?}", side_to_move));
+            }
+            fn on_move(&self, color: CheckersColor, board_before: &Board, game_move: &GameMove, board_after: &Board) {
+                assert_ne!(board_before.get_board(), board_after.get_board());
+                self.events.borrow_mut().push(format!("move {:?} {}", color, game_move));
+            }
+            fn on_capture(&self, _color: CheckersColor, _game_move: &GameMove, captured: &[(usize, usize)]) {
+                self.events.borrow_mut().push(format!("capture {}", captured.len()));
+            }
+            fn on_promotion(&self, color: CheckersColor, x: usize, y: usize) {
+                self.events.borrow_mut().push(format!("promotion {:?} {} {}", color, x, y));
+            }
+            fn on_undo_rejected(&self, side_to_move: CheckersColor) {
+                self.events.borrow_mut().push(format!("undo rejected {:?}", side_to_move));
+            }
+            fn on_draw_declined(&self, offered_by: CheckersColor) {
+                self.events.borrow_mut().push(format!("draw declined {:?}", offered_by));
+            }
+            fn on_game_end(&self, _board: &Board, result: GameResult) {
+                self.events.borrow_mut().push(format!("end {}", result));
+            }
+        }
+
+        struct Scripted {
+            name: String,
+            decisions: RefCell<Vec<Decision>>,
+        }
+
+        impl Player for Scripted {
+            fn move_piece(&self, _possible_moves: &[SimpleMove], _board: Board, _allow_first_random: bool) -> Decision {
+                self.decisions.borrow_mut().remove(0)
+            }
+            fn capture(&self, _possible_captures: &[&Vec<Jump>], _board: Board, _allow_first_random: bool) -> Decision {
+                self.decisions.borrow_mut().remove(0)
+            }
+            fn get_name(&self) -> &String {
+                &self.name
+            }
+            fn set_color(&mut self, _color: CheckersColor) {}
+            fn get_color(&self) -> CheckersColor {
+                CheckersColor::White
+            }
+        }
+
+        let mut white = DummyBot::new("White", CheckersColor::White);
+        let mut black = DummyBot::new("Black", CheckersColor::Black);
+        let recorder = Recorder::default();
+        let mut game = Game::new_with_bots(&mut white, &mut black, 2, 1);
+        let (board, _) = notation::board_from_fen("W:W5:B12").unwrap();
+        game.load(SavedGame {
+            pawn_rows: 1,
+            board,
+            side_to_move: CheckersColor::White,
+            idle_white: 0,
+            idle_black: 0,
+            random_used: 0,
+            moves: Vec::new(),
+            snapshots: Vec::new(),
+        }).unwrap();
+        game.verbose = false;
+        game.add_observer(&recorder);
+        let result = game.play();
+        let events = recorder.events.borrow();
+        assert_eq!(events[0], "start White");
+        assert_eq!(events[1], "move White A7-B8");
+        assert_eq!(events[2], "promotion White 0 1");
+        assert_eq!(events.last().unwrap(), &format!("end {}", result));
+        assert_eq!(events.iter().filter(|e| e.starts_with("move")).count(), game.moves().len());
+        assert_eq!(events.iter().filter(|e| e.starts_with("capture")).count(),
+            game.moves().iter().filter(|m| m.is_capture()).count());
+
+        let white = Scripted { name: "White".to_string(), decisions: RefCell::new(vec![Decision::Undo, Decision::OfferDraw, Decision::Resign]) };
+        let black = DummyBot::new("Black", CheckersColor::Black);
+        let recorder = Recorder::default();
+        let mut game = Game::new(&white, &black, 3);
+        game.verbose = false;
+        game.add_observer(&recorder);
+        assert_eq!(game.play(), GameResult::Win(CheckersColor::Black));
+        assert_eq!(recorder.events.borrow()[1..3], ["undo rejected White".to_string(), "draw declined White".to_string()]);
+    }
+
+    #[test]
+    fn game_state_test() {
+        use crate::checkers_game::GameResult;
+        use crate::game_state::{GameState, GameStatus};
+
+        assert!(matches!(GameState::try_new(4), Err(CheckersError::InvalidConfiguration(_))));
+        let mut state = GameState::try_new(3).unwrap();
+        assert_eq!(state.side_to_move(), CheckersColor::White);
+        assert_eq!(state.status(), GameStatus::InProgress);
+        let moves = state.legal_moves();
+        assert_eq!(moves.len(), 7);
+        state.apply(&moves[0]).unwrap();
+        assert_eq!(state.side_to_move(), CheckersColor::Black);
+        assert_eq!(state.history(), &moves[..1]);
+        assert!(matches!(state.apply(&moves[1]), Err(CheckersError::WrongColor { .. })));
+        assert_eq!(state.history().len(), 1);
+        assert_eq!(state.undo(), Some(moves[0].clone()));
+        assert_eq!(state.board().get_board(), Board::new(3).get_board());
+        assert_eq!(state.undo(), None);
+
+        state.resign(CheckersColor::White);
+        assert_eq!(state.status(), GameStatus::Finished(GameResult::Win(CheckersColor::Black)));
+        assert!(state.legal_moves().is_empty());
+        assert!(matches!(state.apply(&moves[0]), Err(CheckersError::GameOver(_))));
+
+        let (board, color) = notation::board_from_fen("W:W22:B18").unwrap();
+        let mut state = GameState::from_position(board, color);
+        let capture = state.legal_moves();
+        assert!(capture[0].is_capture());
+        state.apply(&capture[0]).unwrap();
+        assert_eq!(state.status(), GameStatus::Finished(GameResult::Win(CheckersColor::White)));
+
+        let (board, color) = notation::board_from_fen("W:WK32:BK1").unwrap();
+        let mut state = GameState::from_position(board, color);
+        while state.status() == GameStatus::InProgress {
+            let quiet = state.legal_moves().into_iter()
+                .find(|m| {
+                    let mut next = state.clone();
+                    next.apply(m).unwrap();
+                    !m.is_capture() && !next.legal_moves().iter().any(|reply| reply.is_capture())
+                })
+                .unwrap();
+            state.apply(&quiet).unwrap();
+        }
+        assert_eq!(state.status(), GameStatus::Finished(GameResult::Draw));
+        assert_eq!(state.idle_counters().0, 8);
+    }
+
+    #[test]
+    #[cfg(feature = "web")]
+    fn web_api_test() {
+        use crate::web::WebApi;
+        let mut api = WebApi::new();
+        let page = api.handle("GET", "/", "");
+        assert_eq!(page.status, 200);
+        assert!(page.content_type.starts_with("text/html"));
+
+        let created = api.handle("POST", "/api/games", r#"{"bot": "Black", "depth": 2}"#);
+        assert_eq!(created.status, 201);
+        let game: serde_json::Value = serde_json::from_str(&created.body).unwrap();
+        assert_eq!(game["side_to_move"], "White");
+        assert_eq!(game["legal_moves"].as_array().unwrap().len(), 7);
+        assert_eq!(game["board"]["white_pawns"].as_array().unwrap().len(), 12);
+
+        let moved = api.handle("POST", "/api/games/1/moves", r#"{"move": "22-18"}"#);
+        assert_eq!(moved.status, 200);
+        let game: serde_json::Value = serde_json::from_str(&moved.body).unwrap();
+        assert_eq!(game["history"].as_array().unwrap().len(), 2);
+        assert_eq!(game["history"][0], "22-18");
+        assert_eq!(game["side_to_move"], "White");
+
+        let illegal = api.handle("POST", "/api/games/1/moves", r#"{"move": "22-17"}"#);
+        assert_eq!(illegal.status, 400);
+        assert!(illegal.body.contains("not a legal move"));
+        assert_eq!(api.handle("POST", "/api/games/1/moves", "{").status, 400);
+
+        let undone = api.handle("POST", "/api/games/1/undo", "");
+        let game: serde_json::Value = serde_json::from_str(&undone.body).unwrap();
+        assert!(game["history"].as_array().unwrap().is_empty());
+
+        let resigned = api.handle("POST", "/api/games/1/resign", "");
+        let game: serde_json::Value = serde_json::from_str(&resigned.body).unwrap();
+        assert_eq!(game["result"]["pdn"], "0-2");
+
+        assert_eq!(api.handle("GET", "/api/games/7", "").status, 404);
+        assert_eq!(api.handle("DELETE", "/api/games/1", "").status, 200);
+        assert_eq!(api.handle("GET", "/api/games/1/moves", "").status, 404);
+    }
+
+    #[test]
+    #[cfg(feature = "wasm")]
+    fn wasm_bindings_test() {
+        use crate::wasm::WasmGame;
+        let mut game = WasmGame::new(3).unwrap();
+        assert_eq!(game.legal_moves().len(), 7);
+        game.apply_move("22-18").unwrap();
+        assert_eq!(game.side_to_move(), "Black");
+        let reply = game.bot_move(3).unwrap();
+        assert_eq!(game.history(), vec!["22-18".to_string(), reply.clone()]);
+        assert_eq!(game.undo(), Some(reply));
+
+        let mut game = WasmGame::from_fen("W:W22:B18").unwrap();
+        assert_eq!(game.best_move(2), Some("22x15".to_string()));
+        game.bot_move(2);
+        assert_eq!(game.result(), Some("2-0".to_string()));
+        assert_eq!(game.best_move(2), None);
+    }
+}
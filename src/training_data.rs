@@ -0,0 +1,153 @@
+// Positions of bot games labelled for training learned estimators.
+//
+// CSV format, one record per line after the header `board,side_to_move,result,score,best_move`:
+//   board         Board::get_board as 32 hexadecimal digits, one nibble per dark square
+//   side_to_move  W or B
+//   result        result of the game: 1 for a White win, -1 for a Black win, 0 for a draw
+//   score         minmax score of the best move for the side to move
+//   best_move     best move of the search in PDN, e.g. 22-18 or 15x22x31
+use std::cell::RefCell;
+use std::io::Write;
+use rand::Rng;
+use crate::{Board, CheckersColor, GameMove, MoveExecutor};
+use crate::analysis;
+use crate::board_estimators::Estimator;
+use crate::checkers_game::GameResult;
+use crate::checkers_utils::CheckersError;
+use crate::notation::{move_from_pdn, move_to_pdn};
+use crate::observers::GameObserver;
+
+pub const CSV_HEADER: &str = "board,side_to_move,result,score,best_move";
+
+#[derive(Clone)]
+pub struct TrainingRecord {
+    pub board: Board,
+    pub side_to_move: CheckersColor,
+    pub result: i8,
+    pub score: i32,
+    pub best_move: GameMove,
+}
+
+fn format_error(message: &str) -> CheckersError {
+    CheckersError::SerializationError(format!("training data: {}", message))
+}
+
+impl TrainingRecord {
+    pub fn to_csv(&self) -> String {
+        let side = match self.side_to_move {
+            CheckersColor::White => "W",
+            CheckersColor::Black => "B",
+        };
+        format!("{:032x},{},{},{},{}", self.board.get_board(), side, self.result, self.score, move_to_pdn(&self.best_move))
+    }
+
+    pub fn from_csv(line: &str) -> Result<Self, CheckersError> {
+        let fields: Vec<&str> = line.trim().split(',').collect();
+        if fields.len() != 5 {
+            return Err(format_error("expected 5 fields"));
+        }
+        let bits = u128::from_str_radix(fields[0], 16).map_err(|_| format_error("invalid board"))?;
+        let board = Board::from_bits(bits)?;
+        let side_to_move = match fields[1] {
+            "W" => CheckersColor::White,
+            "B" => CheckersColor::Black,
+            _ => return Err(format_error("invalid side to move")),
+        };
+        let result = fields[2].parse::<i8>().ok().filter(|r| (-1..=1).contains(r))
+            .ok_or_else(|| format_error("invalid result"))?;
+        let score = fields[3].parse::<i32>().map_err(|_| format_error("invalid score"))?;
+        let best_move = move_from_pdn(&board, side_to_move, fields[4])?;
+        Ok(Self { board, side_to_move, result, score, best_move })
+    }
+}
+
+pub fn write_csv<W: Write>(writer: &mut W, records: &[TrainingRecord]) -> Result<(), CheckersError> {
+    writeln!(writer, "{}", CSV_HEADER)?;
+    for record in records {
+        writeln!(writer, "{}", record.to_csv())?;
+    }
+    Ok(())
+}
+
+pub fn read_csv(text: &str) -> Result<Vec<TrainingRecord>, CheckersError> {
+    let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+    if lines.next().map(str::trim) != Some(CSV_HEADER) {
+        return Err(format_error("missing header"));
+    }
+    lines.map(TrainingRecord::from_csv).collect()
+}
+
+// Collects every position of a game in which a move was played, attach it to a Game with add_observer.
+#[derive(Default)]
+pub struct PositionRecorder {
+    positions: RefCell<Vec<(Board, CheckersColor)>>,
+}
+
+impl PositionRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn take(&self) -> Vec<(Board, CheckersColor)> {
+        self.positions.take()
+    }
+}
+
+impl GameObserver for PositionRecorder {
+    fn on_game_start(&self, _board: &Board, _side_to_move: CheckersColor) {
+        self.positions.borrow_mut().clear();
+    }
+
+    fn on_move(&self, color: CheckersColor, board_before: &Board, _game_move: &GameMove, _board_after: &Board) {
+        self.positions.borrow_mut().push((*board_before, color));
+    }
+
+    // The position after an undo is played again, so it and everything after it is dropped.
+    fn on_undo(&self, board: &Board, side_to_move: CheckersColor) {
+        let mut positions = self.positions.borrow_mut();
+        if let Some(index) = positions.iter().rposition(|(b, c)| b.get_board() == board.get_board() && *c == side_to_move) {
+            positions.truncate(index);
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct ExportConfig {
+    // Search depth of the labels.
+    pub depth: usize,
+    // Positions of the first plies are skipped, they are the same in many games.
+    pub skip_plies: usize,
+    // Probability of keeping a position, between 0 and 1.
+    pub sample_rate: f64,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            depth: 4,
+            skip_plies: 4,
+            sample_rate: 1.0,
+        }
+    }
+}
+
+// Labels a sample of the positions of a finished game. Positions with a pending capture are left out,
+// their score mostly reflects the exchange in progress.
+pub fn label_positions<R: Rng>(positions: &[(Board, CheckersColor)], result: GameResult, config: &ExportConfig,
+                               estimator: &dyn Estimator, rng: &mut R) -> Vec<TrainingRecord> {
+    let result = match result {
+        GameResult::Win(CheckersColor::White) => 1,
+        GameResult::Win(CheckersColor::Black) => -1,
+        GameResult::Draw => 0,
+    };
+    positions.iter()
+        .skip(config.skip_plies)
+        .filter(|(board, color)| MoveExecutor::get_all_captures(board, *color).is_empty())
+        .filter(|_| rng.gen_bool(config.sample_rate.clamp(0.0, 1.0)))
+        .filter_map(|&(board, side_to_move)| {
+            let analysis = analysis::analyse(board, side_to_move, config.depth, estimator);
+            let best = analysis.best()?;
+            Some(TrainingRecord { board, side_to_move, result, score: best.score, best_move: best.game_move.clone() })
+        })
+        .collect()
+}
@@ -0,0 +1,368 @@
+use std::cell::RefCell;
+use std::io::{stdout, Write};
+use std::time::{Duration, Instant};
+use crossterm::{cursor, execute, queue, terminal};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::Print;
+use crate::{Board, CheckersColor, MoveExecutor, Player, SimpleMove};
+use crate::observers::GameObserver;
+use crate::players::Decision;
+use crate::col;
+use crate::col::colored_text;
+use crate::moves::{GameMove, Jump};
+use crate::notation;
+
+const DARK: (usize, usize, usize) = (70, 70, 70);
+const CURSOR: (usize, usize, usize) = (60, 110, 200);
+const SELECTED: (usize, usize, usize) = (200, 170, 40);
+const TARGET: (usize, usize, usize) = (60, 150, 60);
+const MANDATORY: (usize, usize, usize) = (210, 110, 30);
+const CAPTURED: (usize, usize, usize) = (160, 50, 50);
+
+pub struct TuiHuman {
+    name: String,
+    color: CheckersColor,
+    pub(crate) state: RefCell<TuiState>,
+}
+
+pub(crate) struct TuiState {
+    active: bool,
+    cursor: (usize, usize),
+    pub(crate) selected: Vec<(usize, usize)>,
+    pub(crate) input: String,
+    pub(crate) message: String,
+    pub(crate) history: Vec<String>,
+    clocks: [Duration; 2],
+    last_return: Option<Instant>,
+}
+
+impl TuiHuman {
+    pub fn new(name: &str, color: CheckersColor) -> Self {
+        Self {
+            name: name.to_string(),
+            color,
+            state: RefCell::new(TuiState::new()),
+        }
+    }
+
+    fn choose(&self, board: Board, moves: &[GameMove]) -> Decision {
+        let mut state = self.state.borrow_mut();
+        state.start_turn(self.color);
+        if !state.active {
+            let _ = execute!(stdout(), terminal::EnterAlternateScreen, cursor::Hide);
+            state.active = true;
+        }
+        let _ = terminal::enable_raw_mode();
+        state.message = if moves[0].is_capture() {
+            "Capture is mandatory.".to_string()
+        } else {
+            "Your move.".to_string()
+        };
+        let start = Instant::now();
+        let decision = loop {
+            self.draw(&state, &board, moves, Some(start));
+            match event::poll(Duration::from_millis(500)) {
+                Ok(true) => {}
+                _ => continue,
+            }
+            match event::read() {
+                Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                    if let Some(decision) = self.handle_key(&mut state, key, moves) {
+                        break decision;
+                    }
+                }
+                Ok(Event::Resize(_, _)) => {
+                    let _ = execute!(stdout(), terminal::Clear(terminal::ClearType::All));
+                }
+                _ => {}
+            }
+        };
+        let _ = terminal::disable_raw_mode();
+        state.clocks[color_index(self.color)] += start.elapsed();
+        state.selected.clear();
+        state.input.clear();
+        if let Decision::Move(choice) = decision {
+            let new_board = MoveExecutor::promote_to_queen(&MoveExecutor::execute(&board, &moves[choice]));
+            state.message = "Waiting for the opponent...".to_string();
+            self.draw(&state, &new_board, &[], None);
+        }
+        state.last_return = Some(Instant::now());
+        decision
+    }
+
+    pub(crate) fn handle_key(&self, state: &mut TuiState, key: KeyEvent, moves: &[GameMove]) -> Option<Decision> {
+        let (row, column) = state.cursor;
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Some(state.quit()),
+            KeyCode::Char('q') | KeyCode::Char('Q') => return Some(state.quit()),
+            KeyCode::Up => state.cursor = (row.saturating_sub(1), column),
+            KeyCode::Down => state.cursor = ((row + 1).min(7), column),
+            KeyCode::Left => state.cursor = (row, column.saturating_sub(1)),
+            KeyCode::Right => state.cursor = (row, (column + 1).min(7)),
+            KeyCode::Esc => {
+                state.selected.clear();
+                state.input.clear();
+                state.message = "Selection cleared.".to_string();
+            }
+            KeyCode::Backspace => {
+                state.input.pop();
+            }
+            KeyCode::Enter | KeyCode::Char(' ') if state.input.trim().is_empty() => {
+                state.input.clear();
+                let square = self.board_square(state.cursor);
+                return state.select_square(square, moves).map(Decision::Move);
+            }
+            KeyCode::Enter => return state.submit_input(moves).map(Decision::Move),
+            KeyCode::Char(c) if c.is_ascii_alphanumeric() || c == '-' || c == ' ' => state.input.push(c),
+            _ => {}
+        }
+        None
+    }
+
+    fn flipped(&self) -> bool {
+        self.color == CheckersColor::Black
+    }
+
+    fn board_square(&self, (row, column): (usize, usize)) -> (usize, usize) {
+        if self.flipped() {
+            (7 - row, 7 - column)
+        } else {
+            (row, column)
+        }
+    }
+
+    fn draw(&self, state: &TuiState, board: &Board, moves: &[GameMove], turn_start: Option<Instant>) {
+        let board_lines = self.board_lines(state, board, moves);
+        let panel = self.panel_lines(state, turn_start);
+        let mut out = stdout();
+        let _ = queue!(out, cursor::MoveTo(0, 0));
+        let height = board_lines.len().max(panel.len());
+        for i in 0..height {
+            let left = board_lines.get(i).cloned().unwrap_or_else(|| " ".repeat(28));
+            let right = panel.get(i).cloned().unwrap_or_default();
+            let _ = queue!(out, cursor::MoveTo(0, i as u16), Print(left), Print("    "), Print(right),
+                terminal::Clear(terminal::ClearType::UntilNewLine));
+        }
+        let _ = queue!(out, terminal::Clear(terminal::ClearType::FromCursorDown));
+        let _ = out.flush();
+    }
+
+    fn board_lines(&self, state: &TuiState, board: &Board, moves: &[GameMove]) -> Vec<String> {
+        let columns: Vec<char> = if self.flipped() { "HGFEDCBA".chars().collect() } else { "ABCDEFGH".chars().collect() };
+        let header = format!("   {}  ", columns.iter().map(|c| format!(" {} ", c)).collect::<String>());
+        let candidates = if state.selected.is_empty() {
+            Vec::new()
+        } else {
+            notation::moves_through(moves, &state.selected)
+        };
+        let mut lines = vec![header.clone()];
+        for row in 0..8 {
+            let rank = if self.flipped() { row + 1 } else { 8 - row };
+            let mut line = format!("{} ", rank);
+            for column in 0..8 {
+                let (x, y) = self.board_square((row, column));
+                let background = if (x + y).is_multiple_of(2) {
+                    None
+                } else if state.cursor == (row, column) {
+                    Some(CURSOR)
+                } else if state.selected.contains(&(x, y)) {
+                    Some(SELECTED)
+                } else if candidates.iter().any(|&i| moves[i].end_pair() == (x, y)) {
+                    Some(TARGET)
+                } else if candidates.iter().any(|&i| moves[i].captured().contains(&(x, y))) {
+                    Some(CAPTURED)
+                } else if state.selected.is_empty() && moves.iter().any(|m| m.start_pair() == (x, y)) {
+                    if moves[0].is_capture() { Some(MANDATORY) } else { Some(TARGET) }
+                } else {
+                    Some(DARK)
+                };
+                let cell = match (background, board.get_at(x, y)) {
+                    (None, _) => colored_text("   ", col::NONE, col::bg::WHITE, true),
+                    (Some((r, g, b)), Ok(Some(piece))) =>
+                        colored_text(&format!(" {} ", piece.colored_marker(false)), col::NONE, &col::bg::color(r, g, b), true),
+                    (Some((r, g, b)), _) => colored_text("   ", col::NONE, &col::bg::color(r, g, b), true),
+                };
+                line = format!("{}{}", line, cell);
+            }
+            lines.push(format!("{} {}", line, rank));
+        }
+        lines.push(header);
+        lines
+    }
+
+    fn panel_lines(&self, state: &TuiState, turn_start: Option<Instant>) -> Vec<String> {
+        let mut clocks = state.clocks;
+        if let Some(start) = turn_start {
+            clocks[color_index(self.color)] += start.elapsed();
+        }
+        let mut lines = vec![
+            format!("{} playing {:?}", self.name, self.color),
+            format!("White {}   Black {}", format_clock(clocks[0]), format_clock(clocks[1])),
+            String::new(),
+            "Moves:".to_string(),
+        ];
+        let first_shown = state.history.len().saturating_sub(12) / 2 * 2;
+        for (i, pair) in state.history[first_shown..].chunks(2).enumerate() {
+            lines.push(format!("{:>3}. {:<14} {}", first_shown / 2 + i + 1, pair[0], pair.get(1).cloned().unwrap_or_default()));
+        }
+        lines.push(String::new());
+        lines.push(format!("> {}", state.input));
+        lines.push(state.message.clone());
+        lines.push(String::new());
+        lines.push("arrows move, enter/space select, esc cancel".to_string());
+        lines.push("type squares like C3 D4 or 22-18, q quits".to_string());
+        lines
+    }
+}
+
+impl TuiState {
+    pub(crate) fn new() -> Self {
+        Self {
+            active: false,
+            cursor: (5, 0),
+            selected: Vec::new(),
+            input: String::new(),
+            message: String::new(),
+            history: Vec::new(),
+            clocks: [Duration::ZERO; 2],
+            last_return: None,
+        }
+    }
+
+    fn start_turn(&mut self, color: CheckersColor) {
+        if let Some(last_return) = self.last_return {
+            self.clocks[color_index(color.opposite_color())] += last_return.elapsed();
+        }
+    }
+
+    pub(crate) fn select_square(&mut self, square: (usize, usize), moves: &[GameMove]) -> Option<usize> {
+        if self.selected.is_empty() {
+            if moves.iter().any(|m| m.start_pair() == square) {
+                self.selected.push(square);
+                self.message = "Select the target square.".to_string();
+            } else if moves[0].is_capture() {
+                self.message = "Capture is mandatory, select a highlighted piece.".to_string();
+            } else {
+                self.message = "This piece cannot move.".to_string();
+            }
+            return None;
+        }
+        if self.selected.len() == 1 && self.selected[0] == square {
+            self.selected.clear();
+            self.message = "Selection cleared.".to_string();
+            return None;
+        }
+        let mut path = self.selected.clone();
+        path.push(square);
+        let matching = notation::moves_matching_path(moves, &path);
+        if matching.len() == 1 {
+            return Some(matching[0]);
+        }
+        if notation::moves_through(moves, &path).is_empty() {
+            self.message = "Illegal target square.".to_string();
+        } else {
+            self.selected = path;
+            self.message = "Several captures are possible, select the next landing square.".to_string();
+        }
+        None
+    }
+
+    pub(crate) fn submit_input(&mut self, moves: &[GameMove]) -> Option<usize> {
+        let squares: Result<Vec<(usize, usize)>, _> = self.input
+            .split(|c: char| !c.is_ascii_alphanumeric() || c == 'x' || c == 'X')
+            .filter(|token| !token.is_empty())
+            .map(notation::parse_square)
+            .collect();
+        self.input.clear();
+        let squares = match squares {
+            Ok(squares) if squares.len() > 1 => squares,
+            _ => {
+                self.message = "Type a move like C3 D4 or c3-d4.".to_string();
+                return None;
+            }
+        };
+        let matching = notation::moves_matching_path(moves, &squares);
+        match matching.len() {
+            1 => Some(matching[0]),
+            0 => {
+                self.message = "Illegal move.".to_string();
+                None
+            }
+            _ => {
+                self.message = "Ambiguous move, type the full capture path.".to_string();
+                None
+            }
+        }
+    }
+
+    // Leaves the game by resigning, so that it ends as usual.
+    fn quit(&mut self) -> Decision {
+        if self.active {
+            let _ = terminal::disable_raw_mode();
+            let _ = execute!(stdout(), terminal::LeaveAlternateScreen, cursor::Show);
+            self.active = false;
+        }
+        Decision::Resign
+    }
+}
+
+// The move list is taken from the moves the game plays, also those of the opponent.
+impl GameObserver for TuiHuman {
+    fn on_move(&self, _color: CheckersColor, _board_before: &Board, game_move: &GameMove, _board_after: &Board) {
+        self.state.borrow_mut().history.push(game_move.to_string());
+    }
+
+    fn on_undo(&self, _board: &Board, _side_to_move: CheckersColor) {
+        let history = &mut self.state.borrow_mut().history;
+        history.truncate(history.len().saturating_sub(2));
+    }
+}
+
+impl Drop for TuiHuman {
+    fn drop(&mut self) {
+        if self.state.borrow().active {
+            let _ = terminal::disable_raw_mode();
+            let _ = execute!(stdout(), terminal::LeaveAlternateScreen, cursor::Show);
+        }
+    }
+}
+
+impl Player for TuiHuman {
+    fn move_piece(&self, possible_moves: &[SimpleMove], board: Board, _allow_first_random: bool) -> Decision {
+        let moves: Vec<GameMove> = possible_moves.iter().map(|&m| GameMove::Simple(m)).collect();
+        self.choose(board, &moves)
+    }
+
+    fn capture(&self, possible_captures: &[&Vec<Jump>], board: Board, _allow_first_random: bool) -> Decision {
+        let moves: Vec<GameMove> = possible_captures.iter().map(|c| GameMove::Capture(c.to_vec())).collect();
+        self.choose(board, &moves)
+    }
+
+    fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    fn set_color(&mut self, color: CheckersColor) {
+        self.color = color;
+    }
+
+    fn get_color(&self) -> CheckersColor {
+        self.color
+    }
+
+    fn observer(&self) -> Option<&dyn GameObserver> {
+        Some(self)
+    }
+}
+
+fn color_index(color: CheckersColor) -> usize {
+    match color {
+        CheckersColor::White => 0,
+        CheckersColor::Black => 1,
+    }
+}
+
+fn format_clock(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
@@ -0,0 +1,98 @@
+// The module is built with: cargo rustc --lib --release --target wasm32-unknown-unknown --no-default-features
+// --features wasm --crate-type cdylib, followed by wasm-bindgen on the checkers.wasm it writes.
+use wasm_bindgen::prelude::*;
+use crate::{CountEstimator, GameMove, GameState, GameStatus, MinMaxBot, MoveExecutor};
+use crate::notation::{board_from_fen, fen_from_board, move_from_pdn, move_to_pdn};
+
+#[wasm_bindgen]
+pub struct WasmGame {
+    state: GameState,
+}
+
+#[wasm_bindgen]
+impl WasmGame {
+    #[wasm_bindgen(constructor)]
+    pub fn new(pawn_rows: usize) -> Result<WasmGame, JsError> {
+        if !(1..=3).contains(&pawn_rows) {
+            return Err(JsError::new("pawn rows should be between 1 and 3"));
+        }
+        Ok(Self {
+            state: GameState::new(pawn_rows),
+        })
+    }
+
+    #[wasm_bindgen(js_name = fromFen)]
+    pub fn from_fen(fen: &str) -> Result<WasmGame, JsError> {
+        let (board, color) = board_from_fen(fen)?;
+        Ok(Self {
+            state: GameState::from_position(board, color),
+        })
+    }
+
+    pub fn fen(&self) -> String {
+        fen_from_board(&self.state.board(), self.state.side_to_move())
+    }
+
+    #[wasm_bindgen(js_name = sideToMove)]
+    pub fn side_to_move(&self) -> String {
+        format!("{:?}", self.state.side_to_move())
+    }
+
+    #[wasm_bindgen(js_name = legalMoves)]
+    pub fn legal_moves(&self) -> Vec<String> {
+        self.state.legal_moves().iter().map(move_to_pdn).collect()
+    }
+
+    pub fn history(&self) -> Vec<String> {
+        self.state.history().iter().map(move_to_pdn).collect()
+    }
+
+    #[wasm_bindgen(js_name = applyMove)]
+    pub fn apply_move(&mut self, text: &str) -> Result<(), JsError> {
+        let game_move = move_from_pdn(&self.state.board(), self.state.side_to_move(), text)?;
+        self.state.apply(&game_move)?;
+        Ok(())
+    }
+
+    pub fn undo(&mut self) -> Option<String> {
+        self.state.undo().map(|m| move_to_pdn(&m))
+    }
+
+    // PDN result ("2-0", "0-2" or "1-1"), undefined while the game is in progress.
+    pub fn result(&self) -> Option<String> {
+        match self.state.status() {
+            GameStatus::InProgress => None,
+            GameStatus::Finished(result) => Some(result.pdn_result().to_string()),
+        }
+    }
+
+    #[wasm_bindgen(js_name = bestMove)]
+    pub fn best_move(&self, depth: usize) -> Option<String> {
+        self.search(depth).map(|m| move_to_pdn(&m))
+    }
+
+    #[wasm_bindgen(js_name = botMove)]
+    pub fn bot_move(&mut self, depth: usize) -> Option<String> {
+        let game_move = self.search(depth)?;
+        self.state.apply(&game_move).ok()?;
+        Some(move_to_pdn(&game_move))
+    }
+}
+
+impl WasmGame {
+    fn search(&self, depth: usize) -> Option<GameMove> {
+        if self.state.status() != GameStatus::InProgress || depth == 0 {
+            return None;
+        }
+        let estimator = CountEstimator::new(1, 3);
+        let bot = MinMaxBot::new("wasm", self.state.side_to_move(), depth, &estimator);
+        bot.analyse(self.state.board(), self.state.side_to_move()).best()
+            .map(|best| best.game_move.clone())
+    }
+}
+
+#[wasm_bindgen(js_name = perft)]
+pub fn perft(fen: &str, depth: usize) -> Result<u64, JsError> {
+    let (board, color) = board_from_fen(fen)?;
+    Ok(MoveExecutor::perft(&board, color, depth))
+}
@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use log::warn;
+use crate::{CheckersColor, CountEstimator, Decision, GameMove, GameState, GameStatus, MinMaxBot};
+use crate::players;
+use crate::checkers_utils::CheckersError;
+use crate::notation::{self, board_from_fen, fen_from_board, move_to_pdn, square_number};
+
+const INDEX: &str = include_str!("web/index.html");
+const MAX_DEPTH: usize = 8;
+
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    fn json(status: u16, value: Value) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: value.to_string(),
+        }
+    }
+
+    fn error(status: u16, err: &CheckersError) -> Self {
+        Self::json(status, json!({ "error": err.to_string() }))
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct NewGameRequest {
+    rows: Option<usize>,
+    fen: Option<String>,
+    bot: Option<CheckersColor>,
+    depth: Option<usize>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct MoveRequest {
+    #[serde(rename = "move")]
+    notation: Option<String>,
+    index: Option<usize>,
+}
+
+struct WebGame {
+    state: GameState,
+    bot: Option<CheckersColor>,
+    depth: usize,
+}
+
+impl WebGame {
+    fn bot_reply(&mut self) {
+        let estimator = CountEstimator::new(1, 3);
+        while self.state.status() == GameStatus::InProgress && Some(self.state.side_to_move()) == self.bot {
+            let color = self.state.side_to_move();
+            let bot = MinMaxBot::new("web", color, self.depth, &estimator);
+            let legal_moves = self.state.legal_moves();
+            match players::decide(&bot, self.state.board(), &legal_moves, false) {
+                Decision::Move(index) => self.state.apply(&legal_moves[index]).unwrap(),
+                _ => break,
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct WebApi {
+    games: HashMap<u64, WebGame>,
+    next_id: u64,
+}
+
+fn squares(path: &[(usize, usize)]) -> Vec<usize> {
+    path.iter().map(|&(x, y)| square_number(x, y).unwrap()).collect()
+}
+
+fn move_json(game_move: &GameMove) -> Value {
+    json!({
+        "notation": move_to_pdn(game_move),
+        "path": squares(&game_move.path()),
+        "captured": squares(&game_move.captured()),
+    })
+}
+
+fn game_json(id: u64, game: &WebGame) -> Value {
+    let state = &game.state;
+    let result = match state.status() {
+        GameStatus::InProgress => Value::Null,
+        GameStatus::Finished(result) => json!({ "pdn": result.pdn_result(), "text": result.to_string() }),
+    };
+    json!({
+        "id": id,
+        "board": state.board(),
+        "fen": fen_from_board(&state.board(), state.side_to_move()),
+        "side_to_move": state.side_to_move(),
+        "bot": game.bot,
+        "result": result,
+        "legal_moves": state.legal_moves().iter().map(move_json).collect::<Vec<Value>>(),
+        "history": state.history().iter().map(move_to_pdn).collect::<Vec<String>>(),
+    })
+}
+
+fn parse_body<'a, T: Deserialize<'a> + Default>(body: &'a str) -> Result<T, CheckersError> {
+    if body.trim().is_empty() {
+        return Ok(T::default());
+    }
+    serde_json::from_str(body).map_err(|err| CheckersError::Protocol(format!("invalid request body: {}", err)))
+}
+
+impl WebApi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn handle(&mut self, method: &str, url: &str, body: &str) -> Response {
+        let path = url.split('?').next().unwrap_or("");
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        match (method, segments.as_slice()) {
+            ("GET", []) | ("GET", ["index.html"]) => Response {
+                status: 200,
+                content_type: "text/html; charset=utf-8",
+                body: INDEX.to_string(),
+            },
+            ("POST", ["api", "games"]) => match self.create_game(body) {
+                Ok(id) => Response::json(201, game_json(id, &self.games[&id])),
+                Err(err) => Response::error(400, &err),
+            },
+            (_, ["api", "games", id, rest @ ..]) => {
+                let id = match id.parse::<u64>() {
+                    Ok(id) if self.games.contains_key(&id) => id,
+                    _ => return Response::error(404, &CheckersError::Protocol(format!("no game with id {}", id))),
+                };
+                if method == "DELETE" && rest.is_empty() {
+                    self.games.remove(&id);
+                    return Response::json(200, json!({ "deleted": id }));
+                }
+                let game = self.games.get_mut(&id).unwrap();
+                let outcome = match (method, rest) {
+                    ("GET", []) => Ok(()),
+                    ("GET", ["moves"]) => {
+                        let moves: Vec<Value> = game.state.legal_moves().iter().map(move_json).collect();
+                        return Response::json(200, json!(moves));
+                    }
+                    ("POST", ["moves"]) => Self::play_move(game, body),
+                    ("POST", ["undo"]) => Self::undo(game),
+                    ("POST", ["resign"]) => {
+                        let color = game.state.side_to_move();
+                        game.state.resign(color);
+                        Ok(())
+                    }
+                    _ => return Response::error(405, &CheckersError::Protocol(format!("{} {} is not supported", method, path))),
+                };
+                match outcome {
+                    Ok(()) => Response::json(200, game_json(id, game)),
+                    Err(err) => Response::error(400, &err),
+                }
+            }
+            _ => Response::error(404, &CheckersError::Protocol(format!("{} {} is not supported", method, path))),
+        }
+    }
+
+    fn create_game(&mut self, body: &str) -> Result<u64, CheckersError> {
+        let request: NewGameRequest = parse_body(body)?;
+        let depth = request.depth.unwrap_or(4);
+        if !(1..=MAX_DEPTH).contains(&depth) {
+            return Err(CheckersError::InvalidConfiguration(format!("depth should be between 1 and {}", MAX_DEPTH)));
+        }
+        let state = match request.fen {
+            Some(fen) => {
+                let (board, color) = board_from_fen(&fen)?;
+                GameState::from_position(board, color)
+            }
+            None => {
+                let rows = request.rows.unwrap_or(3);
+                if !(1..=3).contains(&rows) {
+                    return Err(CheckersError::InvalidConfiguration("rows should be between 1 and 3".to_string()));
+                }
+                GameState::new(rows)
+            }
+        };
+        let mut game = WebGame {
+            state,
+            bot: request.bot,
+            depth,
+        };
+        game.bot_reply();
+        self.next_id += 1;
+        self.games.insert(self.next_id, game);
+        Ok(self.next_id)
+    }
+
+    fn play_move(game: &mut WebGame, body: &str) -> Result<(), CheckersError> {
+        let request: MoveRequest = parse_body(body)?;
+        if Some(game.state.side_to_move()) == game.bot {
+            return Err(CheckersError::Protocol("it is the bot's turn".to_string()));
+        }
+        let game_move = match (request.notation, request.index) {
+            (Some(text), _) => notation::move_from_pdn(&game.state.board(), game.state.side_to_move(), &text)?,
+            (None, Some(index)) => game.state.legal_moves().get(index).cloned()
+                .ok_or_else(|| CheckersError::Protocol(format!("there is no move with index {}", index)))?,
+            (None, None) => return Err(CheckersError::Protocol("expected a \"move\" or an \"index\"".to_string())),
+        };
+        game.state.apply(&game_move)?;
+        game.bot_reply();
+        Ok(())
+    }
+
+    fn undo(game: &mut WebGame) -> Result<(), CheckersError> {
+        game.state.undo().ok_or_else(|| CheckersError::Protocol("nothing to undo".to_string()))?;
+        while Some(game.state.side_to_move()) == game.bot {
+            if game.state.undo().is_none() {
+                game.bot_reply();
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn serve(address: &str) -> Result<(), CheckersError> {
+    let server = tiny_http::Server::http(address)
+        .map_err(|err| CheckersError::Io { kind: std::io::ErrorKind::Other, message: err.to_string() })?;
+    let mut api = WebApi::new();
+    for mut request in server.incoming_requests() {
+        let mut body = String::new();
+        let response = match request.as_reader().read_to_string(&mut body) {
+            Ok(_) => api.handle(request.method().as_str(), request.url(), &body),
+            Err(err) => Response::error(400, &CheckersError::from(err)),
+        };
+        let header = tiny_http::Header::from_bytes("Content-Type", response.content_type).unwrap();
+        let reply = tiny_http::Response::from_string(response.body)
+            .with_status_code(response.status)
+            .with_header(header);
+        // A client that went away only loses its own reply.
+        if let Err(err) = request.respond(reply) {
+            warn!("Could not send the response: {}", err);
+        }
+    }
+    Ok(())
+}
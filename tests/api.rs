@@ -0,0 +1,90 @@
+use checkers::{Board, CheckersColor, CheckersError, CountEstimator, Decision, DummyBot, Game, GameMove,
+    GameResult, MinMaxBot, MoveExecutor, Player};
+use checkers::notation::{board_from_fen, fen_from_board};
+
+#[test]
+fn initial_position_test() {
+    let board = Board::new(3);
+    assert_eq!(board.pieces_count(CheckersColor::White), 12);
+    assert_eq!(board.pieces_count(CheckersColor::Black), 12);
+    assert_eq!(fen_from_board(&board, CheckersColor::White), "W:W21,22,23,24,25,26,27,28,29,30,31,32:B1,2,3,4,5,6,7,8,9,10,11,12");
+    assert!(matches!(Board::try_new(0), Err(CheckersError::InvalidConfiguration(_))));
+    assert!(Board::try_new(2).is_ok());
+    let squares = board.to_array();
+    assert_eq!(squares[..12], [-1; 12]);
+    assert_eq!(squares[12..20], [0; 8]);
+    assert_eq!(squares[20..], [1; 12]);
+}
+
+#[test]
+fn move_generation_test() {
+    let board = Board::new(3);
+    let moves = MoveExecutor::get_legal_moves(&board, CheckersColor::White);
+    assert_eq!(moves.len(), 7);
+    assert!(moves.iter().all(|m| !m.is_capture()));
+    assert_eq!(MoveExecutor::perft(&board, CheckersColor::White, 4), 1469);
+
+    let (board, color) = board_from_fen("W:W22:B18,3").unwrap();
+    let moves = MoveExecutor::get_legal_moves(&board, color);
+    assert_eq!(moves.len(), 1);
+    let GameMove::Capture(jumps) = &moves[0] else { panic!("not a capture") };
+    assert_eq!(GameMove::try_capture(jumps.clone()).as_ref(), Ok(&moves[0]));
+    assert!(matches!(GameMove::try_capture(Vec::new()), Err(CheckersError::IllegalMove(_))));
+    let board = MoveExecutor::execute(&board, &moves[0]);
+    assert_eq!(board.pieces_count(CheckersColor::Black), 1);
+    assert_eq!(fen_from_board(&board, color.opposite_color()), "B:W15:B3");
+}
+
+#[test]
+fn bot_test() {
+    let estimator = CountEstimator::new(1, 3);
+    let bot = MinMaxBot::new("MinMax", CheckersColor::White, 3, &estimator);
+    let (board, color) = board_from_fen("W:W22,27:B18,10").unwrap();
+    let analysis = bot.analyse(board, color);
+    let best = analysis.best().unwrap();
+    assert!(matches!(best.game_move, GameMove::Capture(_)));
+    assert_eq!(bot.get_color(), CheckersColor::White);
+}
+
+#[test]
+fn game_test() {
+    let mut white = DummyBot::new("White", CheckersColor::White);
+    let mut black = DummyBot::new("Black", CheckersColor::Black);
+    let mut game = Game::new_with_bots(&mut white, &mut black, 2, 2);
+    game.verbose = false;
+    let result = game.play();
+    assert!(!game.moves().is_empty());
+    match result {
+        GameResult::Win(color) => assert!(MoveExecutor::get_legal_moves(&game.board(), color.opposite_color()).is_empty()
+            || game.board().pieces_count(color.opposite_color()) == 0),
+        GameResult::Draw => {}
+    }
+
+    let white = DummyBot::new("White", CheckersColor::White);
+    assert!(matches!(Game::try_new(&white, &white, 3), Err(CheckersError::InvalidConfiguration(_))));
+    let mut white = DummyBot::new("White", CheckersColor::White);
+    let mut black = DummyBot::new("Black", CheckersColor::Black);
+    assert!(matches!(Game::try_new_with_bots(&mut white, &mut black, 3, 3), Err(CheckersError::InvalidConfiguration(_))));
+    assert!(Game::try_new_with_bots(&mut white, &mut black, 2, 3).is_ok());
+    let white = DummyBot::new("White", CheckersColor::White);
+    let moves = MoveExecutor::get_legal_moves(&Board::new(3), CheckersColor::White);
+    let simple: Vec<_> = moves.iter().filter_map(|m| match m {
+        GameMove::Simple(simple) => Some(*simple),
+        GameMove::Capture(_) => None,
+    }).collect();
+    assert!(matches!(white.move_piece(&simple, Board::new(3), false), Decision::Move(i) if i < simple.len()));
+}
+
+#[test]
+fn error_display_test() {
+    let err = board_from_fen("X:W1").err().unwrap();
+    assert!(matches!(err, CheckersError::NotationError { ref text, .. } if text == "X:W1"));
+    assert_eq!(err.to_string(), "invalid notation 'X:W1': a FEN should start with the side to move, W or B");
+    let err = MoveExecutor::execute_checked(&Board::new(3), CheckersColor::White, &MoveExecutor::get_legal_moves(&Board::new(3), CheckersColor::Black)[0]);
+    assert_eq!(err.err().unwrap().to_string(), "the piece on B6 belongs to the other player");
+
+    let io_error = std::fs::read_to_string("/nonexistent/game.pdn").map_err(CheckersError::from);
+    assert!(matches!(io_error, Err(CheckersError::Io { kind: std::io::ErrorKind::NotFound, .. })));
+    let boxed: Box<dyn std::error::Error> = Box::new(CheckersError::NoPiece { x: 5, y: 2 });
+    assert_eq!(boxed.to_string(), "there is no piece on C3");
+}
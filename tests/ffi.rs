@@ -0,0 +1,44 @@
+#![cfg(feature = "ffi")]
+
+use std::path::PathBuf;
+use std::process::Command;
+
+// The checked-in header has to be regenerated after a change of src/ffi.rs: copy checkers.h
+// from the OUT_DIR of the build script to include/.
+#[test]
+fn header_up_to_date_test() {
+    let generated = include_str!(concat!(env!("OUT_DIR"), "/checkers.h"));
+    let checked_in = std::fs::read_to_string(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("include/checkers.h")).unwrap();
+    assert!(generated == checked_in, "include/checkers.h is out of date with src/ffi.rs");
+}
+
+// Builds the cdylib, compiles tests/ffi/test_checkers.c against it and runs the program.
+#[test]
+fn c_program_test() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let target_dir = std::env::current_exe().unwrap().parent().unwrap().parent().unwrap().to_path_buf();
+    let program = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("test_checkers");
+    // The manifest only declares the rlib, the C library is built on request.
+    let mut build = Command::new(env!("CARGO"));
+    build.current_dir(&manifest_dir).args(["rustc", "--lib", "--features", "ffi", "--crate-type", "cdylib"]);
+    if target_dir.ends_with("release") {
+        build.arg("--release");
+    }
+    assert!(build.status().unwrap().success());
+    let status = Command::new(std::env::var("CC").unwrap_or_else(|_| "cc".to_string()))
+        .arg(manifest_dir.join("tests/ffi/test_checkers.c"))
+        .arg("-I").arg(manifest_dir.join("include"))
+        .arg("-L").arg(&target_dir)
+        .arg("-lcheckers")
+        .arg("-o").arg(&program)
+        .status()
+        .expect("a C compiler is needed for this test");
+    assert!(status.success());
+    let output = Command::new(&program)
+        .env("LD_LIBRARY_PATH", &target_dir)
+        .env("DYLD_LIBRARY_PATH", &target_dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "ok\n");
+}
@@ -0,0 +1,88 @@
+#include <assert.h>
+#include <stdio.h>
+#include <string.h>
+#include "checkers.h"
+
+static CheckersMove find_move(CheckersMove *moves, size_t count, uint8_t from, uint8_t to) {
+    for (size_t i = 0; i < count; i++) {
+        if (moves[i].squares[0] == from && moves[i].squares[moves[i].length - 1] == to) {
+            return moves[i];
+        }
+    }
+    assert(!"move not found");
+    return moves[0];
+}
+
+static void board_test(void) {
+    CheckersBoard *board = checkers_board_new(3);
+    assert(board != NULL);
+    assert(checkers_board_new(4) == NULL);
+    assert(checkers_board_side_to_move(board) == CHECKERS_SIDE_WHITE);
+    assert(checkers_board_side_to_move(NULL) == CHECKERS_SIDE_INVALID);
+    assert(checkers_board_is_game_over(NULL));
+
+    CheckersMove moves[64];
+    size_t count = checkers_board_legal_moves(board, moves, 64);
+    assert(count == 7);
+    assert(checkers_board_legal_moves(board, NULL, 0) == 7);
+
+    CheckersMove move = find_move(moves, count, 22, 18);
+    assert(checkers_board_apply(board, &move) == CHECKERS_STATUS_OK);
+    assert(checkers_board_side_to_move(board) == CHECKERS_SIDE_BLACK);
+    assert(checkers_board_apply(board, &move) == CHECKERS_STATUS_ILLEGAL_MOVE);
+    move.length = 0;
+    assert(checkers_board_apply(board, &move) == CHECKERS_STATUS_INVALID_ARGUMENT);
+
+    size_t length = checkers_board_fen(board, NULL, 0);
+    char fen[128];
+    assert(length < sizeof(fen));
+    assert(checkers_board_fen(board, fen, sizeof(fen)) == length);
+    assert(strncmp(fen, "B:W18,", 6) == 0);
+    checkers_board_free(board);
+
+    board = checkers_board_from_fen("W:W22:B18");
+    assert(board != NULL);
+    assert(checkers_board_from_fen("W:W33:B1") == NULL);
+    CheckersMove best;
+    int score = 0;
+    assert(checkers_board_search(board, 3, 0, &best, &score) == CHECKERS_STATUS_OK);
+    assert(best.is_capture && best.length == 2 && best.squares[0] == 22 && best.squares[1] == 15);
+    assert(checkers_board_apply(board, &best) == CHECKERS_STATUS_OK);
+    assert(checkers_board_is_game_over(board));
+    assert(checkers_board_search(board, 3, 0, &best, NULL) == CHECKERS_STATUS_NO_MOVES);
+    checkers_board_free(board);
+}
+
+static void game_test(void) {
+    CheckersGame *game = checkers_game_new(3);
+    CheckersMove moves[64];
+    CheckersMove best;
+    assert(checkers_game_undo(game) == CHECKERS_STATUS_NO_MOVES);
+    for (int ply = 0; ply < 6 && checkers_game_outcome(game) == CHECKERS_OUTCOME_IN_PROGRESS; ply++) {
+        assert(checkers_game_search(game, 8, 50, &best, NULL) == CHECKERS_STATUS_OK);
+        assert(checkers_game_apply(game, &best) == CHECKERS_STATUS_OK);
+    }
+    assert(checkers_game_undo(game) == CHECKERS_STATUS_OK);
+    assert(checkers_game_legal_moves(game, moves, 64) > 0);
+
+    CheckersBoard *board = checkers_game_board(game);
+    assert(checkers_board_side_to_move(board) == CHECKERS_SIDE_BLACK);
+    checkers_board_free(board);
+    checkers_game_free(game);
+
+    game = checkers_game_from_fen("W:W22:B18");
+    size_t count = checkers_game_legal_moves(game, moves, 64);
+    assert(count == 1);
+    assert(checkers_game_apply(game, &moves[0]) == CHECKERS_STATUS_OK);
+    assert(checkers_game_outcome(game) == CHECKERS_OUTCOME_WHITE_WINS);
+    assert(checkers_game_outcome(NULL) == CHECKERS_OUTCOME_INVALID);
+    assert(checkers_game_apply(game, &moves[0]) == CHECKERS_STATUS_GAME_OVER);
+    checkers_game_free(game);
+}
+
+int main(void) {
+    board_test();
+    game_test();
+    printf("ok\n");
+    return 0;
+}
@@ -0,0 +1,52 @@
+# Build the module with `maturin develop --features python` and run `python -m unittest tests/python/test_bindings.py`.
+import unittest
+
+import checkers
+
+
+class BindingsTest(unittest.TestCase):
+    def test_initial_position(self):
+        board = checkers.Board()
+        self.assertEqual(board.side_to_move, "White")
+        self.assertEqual(len(board.legal_moves()), 7)
+        self.assertEqual(board.perft(4), 1469)
+        self.assertEqual(board.pieces_count("black"), 12)
+        self.assertEqual(checkers.Board(1).pieces_count("white"), 4)
+        with self.assertRaises(ValueError):
+            checkers.Board(4)
+
+    def test_moves(self):
+        board = checkers.Board().apply("22-18")
+        self.assertEqual(board.side_to_move, "Black")
+        self.assertEqual(board.fen(), checkers.Board.from_fen(board.fen()).fen())
+        with self.assertRaises(ValueError):
+            board.apply("22-18")
+        with self.assertRaises(ValueError):
+            checkers.Board.from_fen("W:W33:B1")
+
+    def test_game_end_and_search(self):
+        board = checkers.Board.from_fen("W:W22:B18")
+        self.assertFalse(board.is_game_over())
+        self.assertEqual(board.best_move(depth=2), "22x15")
+        board = board.apply(board.best_move())
+        self.assertTrue(board.is_game_over())
+        self.assertEqual(board.winner(), "White")
+        self.assertEqual(board.analyse(), [])
+
+    def test_batch_encoding(self):
+        boards = [checkers.Board(), checkers.Board.from_fen("B:WK1:B32")]
+        data = checkers.encode_batch(boards)
+        self.assertEqual(len(data), 64)
+        rows = [list(int.from_bytes([b], "little", signed=True) for b in data[i:i + 32]) for i in (0, 32)]
+        self.assertEqual(rows[0], boards[0].encode())
+        self.assertEqual(rows[0][:12], [-1] * 12)
+        self.assertEqual(rows[1][0], 2)
+        self.assertEqual(rows[1][31], -1)
+        masks = checkers.legal_move_masks(boards[:1])
+        self.assertEqual(len(masks), 32 * 32)
+        self.assertEqual(sum(masks), 7)
+        self.assertEqual(masks[21 * 32 + 17], 1)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,35 @@
+// Run with: cargo test --target wasm32-unknown-unknown --no-default-features --features wasm --test wasm
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use wasm_bindgen_test::wasm_bindgen_test;
+use checkers::wasm::{perft, WasmGame};
+
+#[wasm_bindgen_test]
+fn move_generation_test() {
+    let game = WasmGame::new(3).unwrap();
+    assert_eq!(game.legal_moves().len(), 7);
+    assert_eq!(perft(&game.fen(), 4).unwrap(), 1469);
+    assert!(WasmGame::new(4).is_err());
+    assert!(WasmGame::from_fen("W:W33:B1").is_err());
+}
+
+#[wasm_bindgen_test]
+fn play_test() {
+    let mut game = WasmGame::new(3).unwrap();
+    game.apply_move("22-18").unwrap();
+    assert_eq!(game.side_to_move(), "Black");
+    assert!(game.apply_move("22-18").is_err());
+    let reply = game.bot_move(3).unwrap();
+    assert_eq!(game.history(), vec!["22-18".to_string(), reply.clone()]);
+    assert_eq!(game.undo(), Some(reply));
+    assert_eq!(game.side_to_move(), "Black");
+}
+
+#[wasm_bindgen_test]
+fn bot_search_test() {
+    let mut game = WasmGame::from_fen("W:W22:B18").unwrap();
+    assert_eq!(game.best_move(2), Some("22x15".to_string()));
+    game.bot_move(2);
+    assert_eq!(game.result(), Some("2-0".to_string()));
+    assert_eq!(game.best_move(2), None);
+}